@@ -1,10 +1,12 @@
 use crate::state::Angle;
 
 /// L System actions
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub enum Do {
     Forward(f64),
     Backward(f64),
+    /// Forward without drawing, the pen state is unchanged
+    Move(f64),
     Left(f64),
     Right(f64),
     PenUp,
@@ -16,5 +18,33 @@ pub enum Do {
     SaveAndTurn(Angle),
     RestoreAndTurn(Angle),
     LineSize(f64),
-    PenColor(f64, f64, f64)
+    PenColor(f64, f64, f64),
+    /// Tropism vector and susceptibility
+    Tropism(f64, f64, f64),
+    /// Tropism susceptibility, keeping the vector
+    Susceptibility(f64),
+    /// Multiply the line size until the state is restored
+    ScaleWidth(f64),
+    /// Multiply the forward and backward lengths until the state is restored
+    ScaleLength(f64),
+    /// Swap the left and right turns until the state is restored
+    SwapTurns,
+    /// Start a filled polygon
+    BeginPolygon,
+    /// Add the position to the current polygon, a dot outside of a polygon
+    Vertex,
+    /// Close and fill the current polygon
+    EndPolygon
+}
+
+impl Do {
+    /// Same action with its lengths multiplied by `factor`
+    pub fn scaled(&self, factor: f64) -> Do {
+        match self {
+            Do::Forward(length) => Do::Forward(length * factor),
+            Do::Backward(length) => Do::Backward(length * factor),
+            Do::Move(length) => Do::Move(length * factor),
+            action => action.clone()
+        }
+    }
 }
@@ -0,0 +1,399 @@
+use std::io::{
+    self,
+    BufRead,
+    Write
+};
+
+use lindenmayer_graphic::{
+    lindenmayer::{Lindenmayer, DEFAULT_TABLE},
+    models::{
+        rules_model::Rules,
+        action_model::Action,
+        export_model::Export
+    },
+    bounds::Bounds,
+    exports::{
+        Format,
+        terminal::Terminal
+    },
+    grammar::parse_action,
+    renders::renderer::Renderer,
+    rule::Rule,
+    state::SizeType
+};
+
+const HELP: &str = "\
+Commands:
+  axiom <value>                 Set the initial state, back to generation 0
+  consts <symbols>              Declare constant symbols
+  rule <symbol> <value> [w]     Set the rule of a symbol, the value may contain
+                                spaces, a last number is the weight
+  alt <symbol> <value> <w>      Add a stochastic alternative to a rule
+  table <name> <symbol> <value> Set the rule of a symbol in a rules table
+  schedule <table> [table..]    Use the tables in turn, one per step, `-` for
+                                the rules, without table to only use the rules
+  action <symbol> <action>      Bind an action, e.g `action F forward 10`
+  interpret <symbol> <value>    Draw the symbol as value, without rewriting it
+  decompose <symbol> <value>    Split the symbol after each step
+  seed <n>                      Set the random seed
+  iterate [n]                   Apply the rules n times (1 by default)
+  show                          Preview the figure in the terminal
+  stats                         Print the state and figure statistics
+  save <file>                   Export the figure, the format comes from the extension
+  undo                          Cancel the last change
+  reset                         Start again from an empty system
+  help                          Print this help
+  quit                          Leave";
+
+/// Symbols of the state printed after each change
+const PREVIEW_LENGTH: usize = 72;
+
+/// Exploration session, every change is kept to be undone
+struct Session {
+    system: Lindenmayer,
+    /// Symbols declared as constants
+    consts: Vec<char>,
+    /// Symbols having a rule
+    vars: Vec<char>,
+    /// Names of the rules tables
+    tables: Vec<String>,
+    seed: u64,
+    /// Seed at the beginning of the session
+    origin: u64,
+    /// Steps applied since the axiom
+    generation: usize,
+    /// Changes applied since the beginning, in order
+    history: Vec<String>
+}
+
+fn system(seed: u64) -> Lindenmayer {
+    let mut system = Lindenmayer::new(Renderer::Recorder(SizeType::Auto));
+
+    system.set_seed(seed);
+
+    system
+}
+
+impl Session {
+    fn new(seed: u64) -> Self {
+        Self {
+            system: system(seed),
+            consts: Vec::new(),
+            vars: Vec::new(),
+            tables: Vec::new(),
+            seed,
+            origin: seed,
+            generation: 0,
+            history: Vec::new()
+        }
+    }
+
+    /// Read a single symbol
+    fn symbol(word: Option<&&str>) -> Result<char, String> {
+        let word = word.ok_or("missing symbol")?;
+        let mut chars = word.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("`{}` is not a single symbol", word))
+        }
+    }
+
+    /// Read a number
+    fn number<T: std::str::FromStr>(word: Option<&&str>) -> Result<T, String> {
+        let word = word.ok_or("missing number")?;
+
+        word
+            .parse()
+            .map_err(| _ | format!("`{}` is not a valid number", word))
+    }
+
+    /// Apply a change and remember it
+    fn change(&mut self, line: &str) -> Result<(), String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let (command, args) = match words.split_first() {
+            Some(split) => split,
+            None => return Ok(())
+        };
+
+        match *command {
+            "axiom" => {
+                self.system.set_axiom(&args.concat());
+                self.generation = 0;
+            },
+            "consts" => {
+                let consts: Vec<char> = args.concat().chars().collect();
+
+                if let Some(c) = consts.iter().find(| c | self.vars.contains(c)) {
+                    return Err(format!("`{}` already has a rule", c));
+                }
+
+                let new: String = consts
+                    .into_iter()
+                    .filter(| c | !self.consts.contains(c))
+                    .collect();
+
+                self.consts.extend(new.chars());
+                self.system.set_consts(&new);
+            },
+            "rule" | "alt" => {
+                let src = Self::symbol(args.first())?;
+                let words = &args[1..];
+
+                // A number after the value is the weight
+                let weight = match words.split_last() {
+                    Some((last, value)) if !value.is_empty() => last.parse::<usize>().ok(),
+                    _ => None
+                };
+                let value = match weight {
+                    Some(_) => words[..words.len() - 1].concat(),
+                    None => words.concat()
+                };
+                let weight = match (*command, weight) {
+                    (_, Some(weight)) => weight,
+                    ("rule", None) => 1,
+                    _ => return Err(String::from("an alternative needs a weight"))
+                };
+
+                if value.is_empty() {
+                    return Err(String::from("missing rule value"));
+                }
+
+                if self.consts.contains(&src) {
+                    return Err(format!("`{}` is a constant", src));
+                }
+
+                let mut rules = match (*command, self.system.get_rule(src)) {
+                    ("alt", Some(rules)) => rules.clone(),
+                    _ => Vec::new()
+                };
+
+                rules.push(Rule::new(&value, weight));
+                self.system.set_rule(src, rules);
+
+                if !self.vars.contains(&src) {
+                    self.vars.push(src);
+                }
+            },
+            "table" => {
+                let name = args.first().ok_or("missing table name")?;
+                let src = Self::symbol(args.get(1))?;
+                let value = args[2..].concat();
+
+                if value.is_empty() {
+                    return Err(String::from("missing rule value"));
+                }
+
+                if self.consts.contains(&src) {
+                    return Err(format!("`{}` is a constant", src));
+                }
+
+                self.system.set_table_rule(name, src, value.as_str());
+
+                if !self.vars.contains(&src) {
+                    self.vars.push(src);
+                }
+
+                if !self.tables.contains(&name.to_string()) {
+                    self.tables.push(name.to_string());
+                }
+            },
+            "schedule" => {
+                let unknown = args
+                    .iter()
+                    .find(| name | **name != DEFAULT_TABLE && !self.tables.contains(&name.to_string()));
+
+                if let Some(name) = unknown {
+                    return Err(format!("unknown table `{}`", name));
+                }
+
+                if args.is_empty() {
+                    self.system.clear_schedule();
+                } else {
+                    self.system.set_schedule(args);
+                }
+            },
+            "action" => {
+                let src = Self::symbol(args.first())?;
+                let action = parse_action(&args[1..], 0).map_err(| e | e.message)?;
+
+                self.system.set_action(src, action);
+            },
+            "interpret" | "decompose" => {
+                let src = Self::symbol(args.first())?;
+                let value = args[1..].concat();
+
+                if value.is_empty() {
+                    return Err(String::from("missing value"));
+                }
+
+                match *command {
+                    "interpret" => self.system.set_interpretation(src, &value),
+                    _ => self.system.set_decomposition(src, &value)
+                };
+            },
+            "seed" => {
+                self.seed = Self::number(args.first())?;
+                self.system.set_seed(self.seed);
+            },
+            "iterate" => {
+                let n = match args.first() {
+                    Some(_) => Self::number(args.first())?,
+                    None => 1
+                };
+
+                self.system.iterate(n);
+                self.generation += n;
+            },
+            "reset" => {
+                self.system = system(self.seed);
+                self.consts.clear();
+                self.vars.clear();
+                self.tables.clear();
+                self.generation = 0;
+            },
+            _ => return Err(format!("unknown command `{}`, try `help`", command))
+        }
+
+        self.history.push(String::from(line));
+
+        Ok(())
+    }
+
+    /// Cancel the last change by applying the previous ones again
+    fn undo(&mut self) -> Result<(), String> {
+        let mut history = self.history.clone();
+
+        history.pop().ok_or("nothing to undo")?;
+
+        let mut session = Session::new(self.origin);
+
+        for line in &history {
+            session.change(line)?;
+        }
+
+        *self = session;
+
+        Ok(())
+    }
+
+    /// Current state, shortened
+    fn summary(&self) -> String {
+        let value = self.system.state().value;
+        let length = value.chars().count();
+        let segments = self.system.record().segments();
+
+        let shown: String = value.chars().take(PREVIEW_LENGTH).collect();
+        let more = if length > PREVIEW_LENGTH { "..." } else { "" };
+
+        format!(
+            "[{}] {}{}\n{} symbol(s), {} segment(s)",
+            self.generation, shown, more, length, segments
+        )
+    }
+
+    fn stats(&self) -> String {
+        let value = self.system.state().value;
+        let drawing = self.system.record();
+        let size = Bounds::from(&drawing).size();
+
+        // Occurrences per symbol, in order of appearance
+        let mut counts: Vec<(char, usize)> = Vec::new();
+
+        for c in value.chars() {
+            match counts.iter_mut().find(| (s, _) | *s == c) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((c, 1))
+            }
+        }
+
+        let counts: Vec<String> = counts
+            .iter()
+            .map(| (c, n) | format!("{}: {}", c, n))
+            .collect();
+
+        format!(
+            "generation {}\nseed {}\n{} symbol(s) ({})\n{} element(s), {} segment(s)\nsize {:.2} x {:.2}",
+            self.generation,
+            self.seed,
+            value.chars().count(),
+            counts.join(", "),
+            drawing.elements.len(),
+            drawing.segments(),
+            size.w,
+            size.h
+        )
+    }
+
+    fn save(&self, filename: Option<&&str>) -> Result<String, String> {
+        let filename = filename.ok_or("missing file name")?;
+        let format = Format::from_filename(filename)
+            .ok_or(format!("unknown format for `{}`", filename))?;
+
+        format
+            .exporter(None)
+            .save(&self.system.record(), filename)
+            .map_err(| e | format!("{}: {}", filename, e))?;
+
+        Ok(format!("Wrote {}", filename))
+    }
+
+    /// Run a command, returns the text to print
+    fn run(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.first().copied() {
+            None => Ok(String::new()),
+            Some("help") => Ok(String::from(HELP)),
+            Some("show") => Ok(format!(
+                "{}\n{}",
+                Terminal::default().export(&self.system.record()),
+                self.summary()
+            )),
+            Some("stats") => Ok(self.stats()),
+            Some("save") => self.save(words.get(1)),
+            Some("undo") => {
+                self.undo()?;
+
+                Ok(self.summary())
+            },
+            Some(_) => {
+                self.change(line)?;
+
+                Ok(self.summary())
+            }
+        }
+    }
+}
+
+fn main() {
+    let mut session = Session::new(rand::random());
+    let stdin = io::stdin();
+
+    println!("Lindenmayer REPL, type `help` for the commands");
+
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Unable to write the prompt");
+
+        let mut line = String::new();
+
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        let line = line.trim();
+
+        if line == "quit" || line == "exit" {
+            break;
+        }
+
+        match session.run(line) {
+            Ok(output) if output.is_empty() => {},
+            Ok(output) => println!("{}", output),
+            Err(error) => println!("error: {}", error)
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    path::Path
+};
+
+use lindenmayer_graphic::{
+    models::export_model::Export,
+    exports::{
+        html::escape,
+        svg::Svg
+    },
+    grammar::Grammar,
+    renders::renderer::Renderer,
+    state::{FitMode, SizeType}
+};
+
+use super::{Options, apply, read, warn_unsupported};
+
+/// A rendered figure of the contact sheet
+struct Entry {
+    /// System name
+    name: String,
+    /// SVG file name, relative to the index
+    image: String,
+    iterations: usize,
+    seed: Option<u64>,
+    symbols: usize,
+    segments: usize
+}
+
+/// Contact sheet page
+const INDEX: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>L-systems gallery</title>
+<style>
+body { font-family: sans-serif; margin: 2em; background: #f4f4f4; }
+main { display: grid; grid-template-columns: repeat(auto-fill, minmax(220px, 1fr)); gap: 1em; }
+figure { margin: 0; padding: 0.5em; background: #fff; border: 1px solid #ddd; }
+figure img { width: 100%; height: 200px; object-fit: contain; }
+figcaption { font-size: 0.85em; }
+dl { display: grid; grid-template-columns: auto 1fr; gap: 0 0.5em; margin: 0.3em 0 0; }
+dt { color: #666; }
+dd { margin: 0; }
+.error { color: #b00020; white-space: pre-wrap; }
+</style>
+</head>
+<body>
+<h1>L-systems gallery</h1>
+"##;
+
+/// Grammar files of `directory`, sorted by name
+fn sources(directory: &str) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(directory)
+        .map_err(| e | format!("{}: {}", directory, e))?;
+
+    let mut files: Vec<String> = entries
+        .filter_map(| entry | entry.ok())
+        .map(| entry | entry.path())
+        .filter(| path | path.is_file())
+        .filter(| path | !path
+            .file_name()
+            .and_then(| name | name.to_str())
+            .unwrap_or(".")
+            .starts_with('.'))
+        .filter_map(| path | path.to_str().map(String::from))
+        .collect();
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Name usable as a file name
+fn file_name(name: &str) -> String {
+    name
+        .chars()
+        .map(| c | if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// Image name prefix of the system `name` read from `source`,
+/// the file stem tells apart the systems of different files
+fn prefix(source: &str, name: &str) -> String {
+    let stem = Path::new(source)
+        .file_stem()
+        .and_then(| stem | stem.to_str())
+        .unwrap_or_default();
+
+    if stem == name {
+        file_name(stem)
+    } else {
+        format!("{}_{}", file_name(stem), file_name(name))
+    }
+}
+
+/// Render every combination of iterations and seeds for a grammar
+fn render(
+    grammar: &Grammar,
+    name: &str,
+    prefix: &str,
+    options: &Options,
+    output: &str
+) -> Result<Vec<Entry>, String> {
+    let iterations = if options.iterations.is_empty() {
+        vec![grammar.iterations]
+    } else {
+        options.iterations.clone()
+    };
+    let seeds: Vec<Option<u64>> = if options.seeds.is_empty() {
+        vec![grammar.seed]
+    } else {
+        options.seeds.iter().map(| seed | Some(*seed)).collect()
+    };
+
+    let exporter = Svg::new(match options.size {
+        Some((w, h)) => SizeType::Fit(w, h, FitMode::Contain),
+        None => SizeType::Auto
+    });
+
+    let mut entries = Vec::new();
+
+    for &n in &iterations {
+        for &seed in &seeds {
+            let mut grammar = grammar.clone();
+
+            grammar.iterations = n;
+            grammar.seed = seed;
+
+            let system = grammar.generate(Renderer::Recorder(SizeType::Auto));
+
+            let drawing = system.record();
+            let image = match seed {
+                Some(seed) => format!("{}_n{}_s{}.svg", prefix, n, seed),
+                None => format!("{}_n{}.svg", prefix, n)
+            };
+            let path = Path::new(output).join(&image);
+
+            exporter
+                .save(&drawing, &path.to_string_lossy())
+                .map_err(| e | format!("{}: {}", path.display(), e))?;
+
+            entries.push(Entry {
+                name: String::from(name),
+                image,
+                iterations: n,
+                seed,
+                symbols: system.state().value.chars().count(),
+                segments: drawing.segments()
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+fn figure(entry: &Entry) -> String {
+    let seed = match entry.seed {
+        Some(seed) => seed.to_string(),
+        None => String::from("random")
+    };
+
+    format!(
+        "<figure>\n<a href=\"{image}\"><img src=\"{image}\" alt=\"{name}\"></a>\n\
+         <figcaption><strong>{name}</strong>\n<dl>\
+         <dt>iterations</dt><dd>{}</dd>\
+         <dt>seed</dt><dd>{}</dd>\
+         <dt>symbols</dt><dd>{}</dd>\
+         <dt>segments</dt><dd>{}</dd>\
+         </dl></figcaption>\n</figure>\n",
+        entry.iterations,
+        seed,
+        entry.symbols,
+        entry.segments,
+        image = escape(&entry.image),
+        name = escape(&entry.name)
+    )
+}
+
+/// Render the grammar files of `directory` into the output directory,
+/// with an `index.html` contact sheet, returns the index path
+///
+/// The grammars failing to load are listed on the sheet
+pub(super) fn run(directory: &str, options: &Options) -> Result<String, String> {
+    let output = options.output.as_deref().unwrap_or("gallery");
+
+    fs::create_dir_all(output)
+        .map_err(| e | format!("{}: {}", output, e))?;
+
+    let mut figures = String::new();
+    let mut errors = String::new();
+
+    for source in sources(directory)? {
+        let result = read(&source, options.system.as_deref())
+            .and_then(| imports | {
+                let mut entries = Vec::new();
+
+                for import in imports {
+                    warn_unsupported(&import);
+
+                    let name = import.name;
+                    let grammar = apply(import.grammar, options)
+                        .map_err(| e | format!("{}: {}", name, e))?;
+
+                    let prefix = prefix(&source, &name);
+
+                    entries.extend(render(&grammar, &name, &prefix, options, output)?);
+                }
+
+                Ok(entries)
+            });
+
+        match result {
+            Ok(entries) => {
+                for entry in &entries {
+                    eprintln!("Rendered {}", entry.image);
+                    figures.push_str(&figure(entry));
+                }
+            },
+            Err(error) => {
+                eprintln!("error: {}: {}", source, error);
+                errors.push_str(&format!(
+                    "<li><strong>{}</strong>: {}</li>\n",
+                    escape(&source),
+                    escape(&error)
+                ));
+            }
+        }
+    }
+
+    let mut index = String::from(INDEX);
+
+    if !errors.is_empty() {
+        index.push_str(&format!("<ul class=\"error\">\n{}</ul>\n", errors));
+    }
+
+    index.push_str(&format!("<main>\n{}</main>\n</body>\n</html>\n", figures));
+
+    let path = Path::new(output).join("index.html");
+
+    fs::write(&path, index)
+        .map_err(| e | format!("{}: {}", path.display(), e))?;
+
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,439 @@
+use std::{
+    env,
+    fs,
+    path::Path,
+    process,
+    thread,
+    time::{Duration, SystemTime}
+};
+
+mod batch;
+
+use lindenmayer_graphic::{
+    exports::Format,
+    grammar::Grammar,
+    imports::{fractint, lpy, Import},
+    presets::Preset,
+    renders::renderer::Renderer,
+    state::SizeType
+};
+
+const USAGE: &str = "\
+Render a L-system into a file
+
+Usage: lindenmayer [OPTIONS] [GRAMMAR]
+       lindenmayer [OPTIONS] --batch <DIR>
+
+Arguments:
+  [GRAMMAR]                    Grammar file, see `lindenmayer_graphic::grammar`,
+                               Fractint or cpfg `.l` file, or L-Py `.lpy` file
+
+Options:
+      --system <NAME>          System to render from a Fractint file [default: first]
+  -p, --preset <NAME>          Start from a preset instead of a file
+  -a, --axiom <AXIOM>          Initial state
+  -c, --consts <CONSTS>        Constant symbols
+  -r, --rule <S=VALUE[:W]>     Rule for the symbol S with an optional weight,
+                               repeat it to add stochastic alternatives
+  -A, --action <S=ACTION>      Action for the symbol S, e.g `F=forward 10`
+  -I, --interpret <S=VALUE>    Symbols drawn in place of S, without rewriting it
+  -D, --decompose <S=VALUE>    Symbols replacing S after each step, until none is left
+  -S, --schedule <T[,T..]>     Rules tables used in turn, one per step,
+                               `-` for the default rules
+  -n, --iterations <N[,N..]>   Amount of steps, several in batch mode
+  -t, --time <T>               Grow the symbols having an age until the time T
+                               instead of iterating
+  -s, --seed <SEED[,SEED..]>   Random seed, several in batch mode
+  -f, --format <FORMAT>        svg, gcode, hpgl, dxf, pdf, eps, tikz, html or txt,
+                               guessed from the output extension by default
+  -o, --output <FILE>          Output file, `-` for the standard output
+                               [default: out.<format extension>]
+                               or output directory in batch mode [default: gallery]
+      --size <WxH>             Canvas size in the format unit
+  -b, --batch <DIR>            Render every grammar file of a directory as SVG
+                               and write an index.html contact sheet
+  -w, --watch                  Render again every time the grammar file is saved
+      --list-presets           Print the preset names
+  -h, --help                   Print this help";
+
+/// Command line options
+#[derive(Debug, Default)]
+struct Options {
+    grammar: Option<String>,
+    /// System picked in a Fractint file
+    system: Option<String>,
+    /// Directory of grammar files
+    batch: Option<String>,
+    preset: Option<Preset>,
+    /// Statements applied over the grammar, in order
+    statements: Vec<String>,
+    /// Rules given on the command line, they replace the grammar ones
+    rules: Vec<String>,
+    iterations: Vec<usize>,
+    seeds: Vec<u64>,
+    format: Option<Format>,
+    output: Option<String>,
+    size: Option<(f64, f64)>,
+    watch: bool
+}
+
+/// Delay between two checks of the grammar file
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Read `WxH`
+fn parse_size(value: &str) -> Result<(f64, f64), String> {
+    let error = || format!("`{}` is not a valid size, expected WxH", value);
+    let (w, h) = value.split_once('x').ok_or_else(error)?;
+
+    match (w.parse(), h.parse()) {
+        (Ok(w), Ok(h)) => Ok((w, h)),
+        _ => Err(error())
+    }
+}
+
+/// Read `N[,N..]`
+fn parse_list<T: std::str::FromStr>(value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(| n | n.trim().parse().map_err(| _ | format!("`{}` is not a valid number", n)))
+        .collect()
+}
+
+/// Turn `S=VALUE` into `S VALUE`
+fn assignment(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((src, dest)) => Ok((String::from(src), String::from(dest))),
+        None => Err(format!("`{}` is not a valid assignment, expected S=VALUE", value))
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args
+            .next()
+            .cloned()
+            .ok_or(format!("`{}` expects a value", arg));
+
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            },
+            "--list-presets" => {
+                for preset in Preset::all() {
+                    println!("{}", preset.name());
+                }
+                process::exit(0);
+            },
+            "-p" | "--preset" => {
+                let name = value()?;
+
+                options.preset = Some(
+                    Preset::from_name(&name)
+                        .ok_or(format!("unknown preset `{}`", name))?
+                );
+            },
+            "-a" | "--axiom" => options.statements.push(format!("axiom {}", value()?)),
+            "-c" | "--consts" => options.statements.push(format!("consts {}", value()?)),
+            "-r" | "--rule" => {
+                let (src, dest) = assignment(&value()?)?;
+                let rule = match dest.rsplit_once(':') {
+                    Some((dest, weight)) => format!("rule {} {} {}", src, dest, weight),
+                    None => format!("rule {} {}", src, dest)
+                };
+
+                options.rules.push(rule);
+            },
+            "-A" | "--action" => {
+                let (src, dest) = assignment(&value()?)?;
+
+                options.statements.push(format!("action {} {}", src, dest));
+            },
+            "-I" | "--interpret" => {
+                let (src, dest) = assignment(&value()?)?;
+
+                options.statements.push(format!("interpret {} {}", src, dest));
+            },
+            "-D" | "--decompose" => {
+                let (src, dest) = assignment(&value()?)?;
+
+                options.statements.push(format!("decompose {} {}", src, dest));
+            },
+            "-S" | "--schedule" => {
+                let tables = value()?.replace(',', " ");
+
+                options.statements.push(format!("schedule {}", tables));
+            },
+            "-t" | "--time" => options.statements.push(format!("time {}", value()?)),
+            "-n" | "--iterations" => options.iterations.extend(parse_list::<usize>(&value()?)?),
+            "-s" | "--seed" => options.seeds.extend(parse_list::<u64>(&value()?)?),
+            "-f" | "--format" => {
+                let name = value()?;
+
+                options.format = Some(
+                    Format::from_name(&name)
+                        .ok_or(format!("unknown format `{}`", name))?
+                );
+            },
+            "--system" => options.system = Some(value()?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "--size" => options.size = Some(parse_size(&value()?)?),
+            "-b" | "--batch" => options.batch = Some(value()?),
+            "-w" | "--watch" => options.watch = true,
+            other if other.starts_with('-') && other != "-" => {
+                return Err(format!("unknown option `{}`", other));
+            },
+            other => {
+                if options.grammar.is_some() {
+                    return Err(String::from("only one grammar file can be given"));
+                }
+
+                options.grammar = Some(String::from(other));
+            }
+        }
+    }
+
+    if options.watch && options.grammar.is_none() {
+        return Err(String::from("--watch needs a grammar file"));
+    }
+
+    if options.batch.is_some() && (options.grammar.is_some() || options.preset.is_some()) {
+        return Err(String::from("--batch cannot be used with a grammar file or a preset"));
+    }
+
+    let several = options.iterations.len() > 1 || options.seeds.len() > 1;
+
+    if several && options.batch.is_none() {
+        return Err(String::from("several iterations or seeds need --batch"));
+    }
+
+    Ok(options)
+}
+
+/// Systems of a file, the Fractint files (`.l`) may hold several
+/// of them, `system` keeps only the one with this name. The Fractint
+/// systems failing to load are skipped with a warning
+///
+/// The L-Py files (`.lpy`) and the cpfg files (`.l` with `-->`
+/// productions) hold a single system
+fn read(filename: &str, system: Option<&str>) -> Result<Vec<Import>, String> {
+    let path = Path::new(filename);
+    let extension = path.extension().and_then(| ext | ext.to_str());
+
+    let cpfg = extension == Some("l") && fs::read_to_string(filename)
+        .map(| source | source.contains("-->"))
+        .unwrap_or(false);
+
+    if extension == Some("lpy") || cpfg {
+        let import = lpy::from_file(filename)
+            .map_err(| e | format!("{}: {}", filename, e))?;
+
+        return Ok(vec![import]);
+    }
+
+    if extension != Some("l") {
+        let name = path
+            .file_stem()
+            .and_then(| stem | stem.to_str())
+            .unwrap_or("grammar");
+        let mut import = Import::new(name);
+
+        import.grammar = Grammar::from_file(filename).map_err(| e | e.to_string())?;
+
+        return Ok(vec![import]);
+    }
+
+    let mut errors = Vec::new();
+    let imports: Vec<Import> = fractint::from_file(filename)
+        .map_err(| e | format!("{}: {}", filename, e))?
+        .into_iter()
+        .filter_map(| system | system
+            .map_err(| e | errors.push(format!("{}: {}", filename, e)))
+            .ok())
+        .filter(| import | system.map_or(true, | name | import.name.eq_ignore_ascii_case(name)))
+        .collect();
+
+    // The bad systems are skipped unless none is left
+    if imports.is_empty() && !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    for error in errors {
+        eprintln!("warning: {}, system skipped", error);
+    }
+
+    if let (Some(name), true) = (system, imports.is_empty()) {
+        return Err(format!("{}: no system named `{}`", filename, name));
+    }
+
+    Ok(imports)
+}
+
+/// Print the commands an imported system could not translate
+fn warn_unsupported(import: &Import) {
+    if !import.unsupported.is_empty() {
+        eprintln!("warning: {}: unsupported commands {}", import.name, import.report());
+    }
+}
+
+/// Grammar described by the options
+fn load(options: &Options) -> Result<Grammar, String> {
+    let mut grammar = match (&options.grammar, options.preset) {
+        (Some(_), Some(_)) => {
+            return Err(String::from("a grammar file and a preset cannot be used together"));
+        },
+        (Some(filename), None) => {
+            let import = read(filename, options.system.as_deref())?
+                .into_iter()
+                .next()
+                .ok_or(format!("{}: no system found", filename))?;
+
+            warn_unsupported(&import);
+
+            import.grammar
+        },
+        (None, Some(preset)) => preset.grammar(),
+        (None, None) => Grammar::new()
+    };
+
+    if let Some(n) = options.iterations.first() {
+        grammar.iterations = *n;
+    }
+
+    if let Some(seed) = options.seeds.first() {
+        grammar.seed = Some(*seed);
+    }
+
+    apply(grammar, options)
+}
+
+/// Apply the command line statements over `grammar`
+fn apply(mut grammar: Grammar, options: &Options) -> Result<Grammar, String> {
+    for statement in &options.statements {
+        grammar
+            .parse_line(statement, 0)
+            .map_err(| e | e.to_string())?;
+    }
+
+    // Command line rules replace the grammar ones for the same symbol
+    let mut inline = Grammar::new();
+
+    for rule in &options.rules {
+        inline
+            .parse_line(rule, 0)
+            .map_err(| e | e.to_string())?;
+    }
+
+    for (src, rules) in inline.rules {
+        grammar.rules.retain(| (c, _) | *c != src);
+        grammar.rules.push((src, rules));
+    }
+
+    grammar.check().map_err(| e | e.to_string())?;
+
+    if grammar.axiom.is_empty() {
+        return Err(String::from("missing axiom, give a grammar file, a preset or --axiom"));
+    }
+
+    Ok(grammar)
+}
+
+/// Render the grammar into the output, returns the written destination
+fn render(options: &Options) -> Result<String, String> {
+    let grammar = load(options)?;
+
+    let format = match (options.format, &options.output) {
+        (Some(format), _) => format,
+        (None, Some(output)) => Format::from_filename(output).unwrap_or(Format::Svg),
+        (None, None) => Format::Svg
+    };
+    let output = match &options.output {
+        Some(output) => output.clone(),
+        None => format!("out.{}", format.extension())
+    };
+
+    let drawing = grammar
+        .generate(Renderer::Recorder(SizeType::Auto))
+        .record();
+    let exporter = format.exporter(options.size);
+
+    if output == "-" {
+        println!("{}", exporter.export(&drawing));
+    } else {
+        exporter
+            .save(&drawing, &output)
+            .map_err(| e | format!("{}: {}", output, e))?;
+    }
+
+    Ok(output)
+}
+
+/// Last modification time of `filename`
+fn modified(filename: &str) -> Option<SystemTime> {
+    fs::metadata(filename)
+        .and_then(| metadata | metadata.modified())
+        .ok()
+}
+
+/// Print the outcome of a render
+fn report(result: &Result<String, String>) {
+    match result {
+        Ok(output) if output != "-" => eprintln!("Wrote {}", output),
+        Ok(_) => {},
+        Err(error) => eprintln!("error: {}", error)
+    }
+}
+
+/// Render every time the grammar file changes, the errors are
+/// printed without stopping
+fn watch(options: &Options) -> ! {
+    let filename = options.grammar.as_deref().unwrap_or_default();
+    let mut last = modified(filename);
+
+    report(&render(options));
+    eprintln!("Watching {}, press Ctrl-C to stop", filename);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let current = modified(filename);
+
+        // Missing while an editor replaces it
+        if current.is_none() || current == last {
+            continue;
+        }
+
+        last = current;
+        report(&render(options));
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+
+    if options.watch {
+        watch(&options);
+    }
+
+    let result = match &options.batch {
+        Some(directory) => batch::run(directory, &options),
+        None => render(&options)
+    };
+
+    report(&result);
+
+    if result.is_err() {
+        process::exit(1);
+    }
+}
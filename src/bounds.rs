@@ -0,0 +1,146 @@
+use crate::{
+    drawing::Drawing,
+    square::Square,
+    state::{Pos, Size}
+};
+
+/// Area covered by the drawn geometry, including the stroke widths
+///
+/// Only the positions given while drawing should be added, moves with
+/// the pen up or jumps back to a saved state are not part of the figure
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bounds {
+    /// Smallest coordinates, `None` until something is drawn
+    min: Option<Pos>,
+    /// Largest coordinates
+    max: Pos,
+    /// Blank space added around the figure
+    margin: f64
+}
+
+impl Bounds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indicate if nothing has been drawn
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none()
+    }
+
+    /// Include a point drawn with a pen of size `width`
+    pub fn add_point(&mut self, point: Pos, width: f64) {
+        let half = width.max(0.) / 2.;
+        let (low, high) = (
+            Pos { x: point.x - half, y: point.y - half },
+            Pos { x: point.x + half, y: point.y + half }
+        );
+
+        match self.min.as_mut() {
+            Some(min) => {
+                min.x = min.x.min(low.x);
+                min.y = min.y.min(low.y);
+                self.max.x = self.max.x.max(high.x);
+                self.max.y = self.max.y.max(high.y);
+            },
+            None => {
+                self.min = Some(low);
+                self.max = high;
+            }
+        }
+    }
+
+    /// Include a segment drawn with a pen of size `width`
+    pub fn add_segment(&mut self, start: Pos, end: Pos, width: f64) {
+        self.add_point(start, width);
+        self.add_point(end, width);
+    }
+
+    /// Include another area
+    pub fn merge(&mut self, other: &Bounds) {
+        if let Some(min) = other.min {
+            self.add_point(min, 0.);
+            self.add_point(other.max, 0.);
+        }
+    }
+
+    /// Forget the drawn geometry, keeping the margin
+    pub fn clear(&mut self) {
+        self.min = None;
+        self.max = Pos::default();
+    }
+
+    /// Set the blank space around the figure
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin.max(0.);
+
+        self
+    }
+
+    /// Smallest coordinates, including the margin
+    pub fn min(&self) -> Pos {
+        let min = self.min.unwrap_or_default();
+
+        Pos {
+            x: min.x - self.margin,
+            y: min.y - self.margin
+        }
+    }
+
+    /// Largest coordinates, including the margin
+    pub fn max(&self) -> Pos {
+        let max = match self.min {
+            Some(_) => self.max,
+            None => Pos::default()
+        };
+
+        Pos {
+            x: max.x + self.margin,
+            y: max.y + self.margin
+        }
+    }
+
+    /// Area size, including the margin
+    pub fn size(&self) -> Size {
+        let (min, max) = (self.min(), self.max());
+
+        Size {
+            w: max.x - min.x,
+            h: max.y - min.y
+        }
+    }
+
+    /// Area center
+    pub fn center(&self) -> Pos {
+        let (min, max) = (self.min(), self.max());
+
+        Pos {
+            x: (min.x + max.x) / 2.,
+            y: (min.y + max.y) / 2.
+        }
+    }
+
+    /// Area as a square, for a y axis pointing up
+    pub fn square(&self) -> Square {
+        let (min, max) = (self.min(), self.max());
+
+        Square {
+            top_left: Pos { x: min.x, y: max.y },
+            bottom_right: Pos { x: max.x, y: min.y }
+        }
+    }
+}
+
+impl From<&Drawing> for Bounds {
+    fn from(drawing: &Drawing) -> Self {
+        let mut bounds = Bounds::new();
+
+        for element in &drawing.elements {
+            for point in element.shape.points() {
+                bounds.add_point(*point, element.style.width);
+            }
+        }
+
+        bounds
+    }
+}
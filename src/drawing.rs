@@ -0,0 +1,158 @@
+use crate::{
+    square::Square,
+    state::Pos
+};
+
+/// RGB color, each component in `0..=255`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64
+}
+
+impl Default for Rgb {
+    fn default() -> Self {
+        Self {
+            r: 0.,
+            g: 0.,
+            b: 0.
+        }
+    }
+}
+
+impl From<(f64, f64, f64)> for Rgb {
+    fn from(color: (f64, f64, f64)) -> Self {
+        Self {
+            r: color.0,
+            g: color.1,
+            b: color.2
+        }
+    }
+}
+
+impl Rgb {
+    /// Components clamped and rounded to bytes
+    pub fn bytes(&self) -> (u8, u8, u8) {
+        let byte = | v: f64 | v.clamp(0., 255.).round() as u8;
+
+        (byte(self.r), byte(self.g), byte(self.b))
+    }
+
+    /// Hexadecimal notation, e.g `#ff8000`
+    pub fn hex(&self) -> String {
+        let (r, g, b) = self.bytes();
+
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+/// Stroke details shared by every point of an element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// Pen color
+    pub color: Rgb,
+    /// Pen line size
+    pub width: f64
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            color: Rgb::default(),
+            width: 1.
+        }
+    }
+}
+
+/// Geometry recorded from the turtle
+#[derive(Debug, Clone)]
+pub enum Shape {
+    /// Connected line segments
+    Polyline(Vec<Pos>),
+    /// Closed and filled area
+    Polygon(Vec<Pos>),
+    /// Single dot
+    Point(Pos)
+}
+
+impl Shape {
+    /// Every vertex of the shape
+    pub fn points(&self) -> &[Pos] {
+        match self {
+            Shape::Polyline(points) => points,
+            Shape::Polygon(points) => points,
+            Shape::Point(point) => std::slice::from_ref(point)
+        }
+    }
+
+    /// Amount of line segments needed to draw the shape
+    pub fn segments(&self) -> usize {
+        match self {
+            Shape::Polyline(points) => points.len().saturating_sub(1),
+            Shape::Polygon(points) => points.len(),
+            Shape::Point(_) => 0
+        }
+    }
+}
+
+/// A shape with its style and its branch depth
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub shape: Shape,
+    pub style: Style,
+    /// Amount of saved states when the element has been drawn
+    pub depth: usize
+}
+
+/// Backend-neutral figure, the coordinates are in turtle units
+/// with the y axis pointing up
+#[derive(Debug, Clone, Default)]
+pub struct Drawing {
+    /// Elements in draw order
+    pub elements: Vec<Element>,
+    /// Background color, transparent if `None`
+    pub background: Option<Rgb>
+}
+
+impl Drawing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an element on top of the others
+    pub fn push(&mut self, element: Element) {
+        self.elements.push(element);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Amount of line segments in the drawing
+    pub fn segments(&self) -> usize {
+        self.elements
+            .iter()
+            .map(| element | element.shape.segments())
+            .sum()
+    }
+
+    /// Area covered by the vertices, `None` for an empty drawing
+    pub fn bounds(&self) -> Option<Square> {
+        let mut points = self.elements
+            .iter()
+            .flat_map(| element | element.shape.points());
+
+        let first = *points.next()?;
+        let mut square = Square {
+            top_left: first,
+            bottom_right: first
+        };
+
+        for point in points {
+            square.update_max_area(*point);
+        }
+
+        Some(square)
+    }
+}
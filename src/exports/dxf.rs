@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    drawing::{Drawing, Rgb},
+    exports::plot
+};
+
+/// AutoCAD DXF (R12 ASCII) writer
+///
+/// The figure is scaled to fit the sheet (in millimeters) and every
+/// pen color gets its own `PEN_<n>` layer, in order of appearance,
+/// colored with the closest AutoCAD color index
+pub struct Dxf {
+    /// Sheet width in mm
+    pub width: f64,
+    /// Sheet height in mm
+    pub height: f64,
+    /// Blank space around the figure in mm
+    pub margin: f64
+}
+
+impl Dxf {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            margin: 10.
+        }
+    }
+
+    /// Set the blank space around the figure in mm
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+}
+
+/// Write a group code and its value
+fn group<T: std::fmt::Display>(out: &mut String, code: u16, value: T) {
+    writeln!(out, "{}\n{}", code, value).unwrap();
+}
+
+/// RGB value of an AutoCAD color index, from 1 to 255
+///
+/// 7 is drawn black or white depending on the background, it is
+/// given as black here, `aci` also matches it with white
+fn aci_rgb(index: u8) -> Rgb {
+    let (r, g, b) = match index {
+        1 => (255., 0., 0.),
+        2 => (255., 255., 0.),
+        3 => (0., 255., 0.),
+        4 => (0., 255., 255.),
+        5 => (0., 0., 255.),
+        6 => (255., 0., 255.),
+        7 => (0., 0., 0.),
+        8 => (128., 128., 128.),
+        9 => (192., 192., 192.),
+        10..=249 => {
+            // 24 hues, 5 values, full then half saturation
+            let hue = (index / 10 - 1) as f64 * 15.;
+            let value = [255., 204., 153., 127., 76.][(index % 10 / 2) as usize];
+            let saturation = if index % 2 == 1 { 0.5 } else { 1. };
+
+            let channel = | n: f64 | {
+                let k = (n + hue / 60.) % 6.;
+                let amount = (k.min(4. - k)).clamp(0., 1.);
+
+                value * (1. - saturation * amount)
+            };
+
+            (channel(5.), channel(3.), channel(1.))
+        },
+        _ => {
+            // Grays
+            let level = [51., 80., 105., 130., 190., 255.][(index - 250) as usize];
+
+            (level, level, level)
+        }
+    };
+
+    (r, g, b).into()
+}
+
+/// Closest AutoCAD color index
+fn aci(color: Rgb) -> u8 {
+    let distance = | other: Rgb | {
+        (color.r - other.r).powi(2)
+            + (color.g - other.g).powi(2)
+            + (color.b - other.b).powi(2)
+    };
+
+    // Black and white are both the index 7
+    let white = distance((255., 255., 255.).into());
+
+    (1..=255)
+        .map(| index | {
+            let d = distance(aci_rgb(index));
+
+            (if index == 7 { d.min(white) } else { d }, index)
+        })
+        .fold((f64::INFINITY, 7), | best, candidate | {
+            if candidate.0 < best.0 { candidate } else { best }
+        })
+        .1
+}
+
+impl Export for Dxf {
+    fn export(&self, drawing: &Drawing) -> String {
+        let strokes = plot::sheet_strokes(
+            drawing,
+            self.width,
+            self.height,
+            self.margin
+        );
+        let palette = plot::palette(&strokes);
+        let mut out = String::new();
+
+        // Header, R12 has no unit, the coordinates are millimeters
+        group(&mut out, 0, "SECTION");
+        group(&mut out, 2, "HEADER");
+        group(&mut out, 9, "$ACADVER");
+        group(&mut out, 1, "AC1009");
+        group(&mut out, 0, "ENDSEC");
+
+        // One layer per pen
+        group(&mut out, 0, "SECTION");
+        group(&mut out, 2, "TABLES");
+        group(&mut out, 0, "TABLE");
+        group(&mut out, 2, "LAYER");
+        group(&mut out, 70, palette.len());
+
+        for (i, color) in palette.iter().enumerate() {
+            group(&mut out, 0, "LAYER");
+            group(&mut out, 2, format!("PEN_{}", i + 1));
+            group(&mut out, 70, 0);
+            group(&mut out, 62, aci(*color));
+            group(&mut out, 6, "CONTINUOUS");
+        }
+
+        group(&mut out, 0, "ENDTAB");
+        group(&mut out, 0, "ENDSEC");
+
+        // Geometry
+        group(&mut out, 0, "SECTION");
+        group(&mut out, 2, "ENTITIES");
+
+        for stroke in &strokes {
+            let layer = format!(
+                "PEN_{}",
+                plot::pen_number(&palette, stroke.color)
+            );
+
+            if stroke.points.len() == 1 {
+                group(&mut out, 0, "POINT");
+                group(&mut out, 8, &layer);
+                group(&mut out, 10, stroke.points[0].x);
+                group(&mut out, 20, stroke.points[0].y);
+
+                continue;
+            }
+
+            group(&mut out, 0, "POLYLINE");
+            group(&mut out, 8, &layer);
+            group(&mut out, 66, 1);
+            // Dummy point, required by R12 readers
+            group(&mut out, 10, 0.);
+            group(&mut out, 20, 0.);
+            group(&mut out, 30, 0.);
+            group(&mut out, 70, 0);
+
+            for point in &stroke.points {
+                group(&mut out, 0, "VERTEX");
+                group(&mut out, 8, &layer);
+                group(&mut out, 10, point.x);
+                group(&mut out, 20, point.y);
+            }
+
+            group(&mut out, 0, "SEQEND");
+            group(&mut out, 8, &layer);
+        }
+
+        group(&mut out, 0, "ENDSEC");
+        group(&mut out, 0, "EOF");
+
+        out
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    drawing::{
+        Drawing,
+        Rgb,
+        Shape
+    },
+    exports::{
+        Frame,
+        PageSize,
+        POINTS_PER_MM
+    }
+};
+
+/// Encapsulated PostScript writer
+///
+/// The drawing is scaled to fit the page inside the margins
+pub struct Eps {
+    /// Physical page size
+    pub page: PageSize,
+    /// Blank space around the figure in mm
+    pub margin: f64
+}
+
+impl Eps {
+    pub fn new(page: PageSize) -> Self {
+        Self {
+            page,
+            margin: 10.
+        }
+    }
+
+    /// Set the blank space around the figure in mm
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+}
+
+impl Default for Eps {
+    fn default() -> Self {
+        Self::new(PageSize::default())
+    }
+}
+
+impl Export for Eps {
+    fn export(&self, drawing: &Drawing) -> String {
+        let (w, h) = self.page.points();
+        let frame = Frame::fit(drawing, w, h, self.margin * POINTS_PER_MM);
+        let color = | c: Rgb | format!(
+            "{:.3} {:.3} {:.3} setrgbcolor",
+            c.r / 255.,
+            c.g / 255.,
+            c.b / 255.
+        );
+
+        let mut out = String::new();
+
+        writeln!(out, "%!PS-Adobe-3.0 EPSF-3.0").unwrap();
+        // Rounding errors must not grow the integer bounding box
+        let ceil = | v: f64 | ((v * 1e3).round() / 1e3).ceil();
+
+        writeln!(out, "%%BoundingBox: 0 0 {} {}", ceil(w), ceil(h)).unwrap();
+        writeln!(out, "%%HiResBoundingBox: 0 0 {:.3} {:.3}", w, h).unwrap();
+        writeln!(out, "%%Creator: lindenmayer_graphic").unwrap();
+        writeln!(out, "%%EndComments").unwrap();
+
+        if let Some(bg) = drawing.background {
+            writeln!(out, "{} 0 0 {:.3} {:.3} rectfill", color(bg), w, h).unwrap();
+        }
+
+        // Round caps and joins
+        out.push_str("1 setlinecap 1 setlinejoin\n");
+
+        for element in &drawing.elements {
+            writeln!(
+                out,
+                "{} {:.3} setlinewidth newpath",
+                color(element.style.color),
+                element.style.width * frame.scale()
+            ).unwrap();
+
+            let points = element.shape.points();
+
+            for (i, point) in points.iter().enumerate() {
+                let (x, y) = frame.map(*point);
+                let op = if i == 0 { "moveto" } else { "lineto" };
+
+                writeln!(out, "{:.3} {:.3} {}", x, h - y, op).unwrap();
+            }
+
+            match element.shape {
+                Shape::Polyline(_) => out.push_str("stroke\n"),
+                Shape::Polygon(_) => out.push_str("closepath gsave fill grestore stroke\n"),
+                Shape::Point(point) => {
+                    // Zero length line, drawn as a dot by the round cap
+                    let (x, y) = frame.map(point);
+
+                    writeln!(out, "{:.3} {:.3} lineto stroke", x, h - y).unwrap();
+                }
+            }
+        }
+
+        out.push_str("showpage\n%%EOF\n");
+
+        out
+    }
+}
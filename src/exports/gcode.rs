@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    drawing::Drawing,
+    exports::plot::{self, Stroke},
+    state::Pos
+};
+
+/// G-code writer for pen plotters
+///
+/// The figure is scaled to fit the work area (in millimeters), the
+/// machine origin is the bottom left corner with the y axis pointing up
+pub struct GCode {
+    /// Work area width in mm
+    pub width: f64,
+    /// Work area height in mm
+    pub height: f64,
+    /// Blank space around the figure in mm
+    pub margin: f64,
+    /// Command lifting the pen
+    pub pen_up: String,
+    /// Command lowering the pen
+    pub pen_down: String,
+    /// Drawing speed in mm/min
+    pub feed_rate: f64,
+    /// Pen-up travel speed in mm/min
+    pub travel_rate: f64,
+    /// Pause (`M0`) between strokes of different colors
+    pub pause_on_color: bool
+}
+
+impl GCode {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            margin: 10.,
+            pen_up: String::from("M3 S0"),
+            pen_down: String::from("M3 S1000"),
+            feed_rate: 1500.,
+            travel_rate: 3000.,
+            pause_on_color: false
+        }
+    }
+
+    /// Set the commands lifting and lowering the pen
+    pub fn set_pen_commands(&mut self, up: &str, down: &str) -> &mut Self {
+        self.pen_up = String::from(up);
+        self.pen_down = String::from(down);
+
+        self
+    }
+
+    /// Set the drawing and the travel speeds in mm/min
+    pub fn set_feed_rates(&mut self, feed: f64, travel: f64) -> &mut Self {
+        self.feed_rate = feed;
+        self.travel_rate = travel;
+
+        self
+    }
+
+    /// Set the blank space around the figure in mm
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Wait for a pen change between colors
+    pub fn set_pause_on_color(&mut self, pause: bool) -> &mut Self {
+        self.pause_on_color = pause;
+
+        self
+    }
+
+    /// Return the optimized strokes in machine coordinates
+    pub fn strokes(&self, drawing: &Drawing) -> Vec<Stroke> {
+        plot::sheet_strokes(drawing, self.width, self.height, self.margin)
+    }
+}
+
+impl Export for GCode {
+    fn export(&self, drawing: &Drawing) -> String {
+        let strokes = self.strokes(drawing);
+        let mut out = String::new();
+
+        writeln!(out, "; {} strokes, {:.1} mm pen-up travel",
+            strokes.len(),
+            plot::travel(&strokes, Pos::default())
+        ).unwrap();
+        writeln!(out, "G21 ; millimeters").unwrap();
+        writeln!(out, "G90 ; absolute positions").unwrap();
+        writeln!(out, "{}", self.pen_up).unwrap();
+
+        let mut color = None;
+
+        for stroke in &strokes {
+            if color != Some(stroke.color) {
+                if self.pause_on_color && color.is_some() {
+                    writeln!(out, "M0").unwrap();
+                }
+
+                writeln!(out, "; pen {}", stroke.color.hex()).unwrap();
+
+                color = Some(stroke.color);
+            }
+
+            let start = stroke.points[0];
+
+            writeln!(out, "G0 X{:.3} Y{:.3} F{}",
+                start.x, start.y, self.travel_rate
+            ).unwrap();
+            writeln!(out, "{}", self.pen_down).unwrap();
+
+            for point in &stroke.points[1..] {
+                writeln!(out, "G1 X{:.3} Y{:.3} F{}",
+                    point.x, point.y, self.feed_rate
+                ).unwrap();
+            }
+
+            writeln!(out, "{}", self.pen_up).unwrap();
+        }
+
+        writeln!(out, "G0 X0 Y0 F{}", self.travel_rate).unwrap();
+
+        out
+    }
+}
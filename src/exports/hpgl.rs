@@ -0,0 +1,92 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    drawing::Drawing,
+    exports::plot
+};
+
+/// Plotter units per millimeter
+const UNITS_PER_MM: f64 = 40.;
+
+/// HP-GL writer
+///
+/// The figure is scaled to fit the sheet (in millimeters) and every
+/// pen color is mapped to a pen number, in order of appearance
+pub struct Hpgl {
+    /// Sheet width in mm
+    pub width: f64,
+    /// Sheet height in mm
+    pub height: f64,
+    /// Blank space around the figure in mm
+    pub margin: f64,
+    /// Amount of pens in the carousel, colors beyond are wrapped around
+    pub pens: usize
+}
+
+impl Hpgl {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            margin: 10.,
+            pens: 8
+        }
+    }
+
+    /// Set the blank space around the figure in mm
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Set the amount of pens available on the plotter
+    pub fn set_pens(&mut self, pens: usize) -> &mut Self {
+        self.pens = pens.max(1);
+
+        self
+    }
+}
+
+impl Export for Hpgl {
+    fn export(&self, drawing: &Drawing) -> String {
+        let strokes = plot::sheet_strokes(
+            drawing,
+            self.width,
+            self.height,
+            self.margin
+        );
+        let palette = plot::palette(&strokes);
+        let unit = | v: f64 | (v * UNITS_PER_MM).round() as i64;
+
+        let mut out = String::from("IN;\n");
+        let mut pen = 0;
+
+        for stroke in &strokes {
+            let number = (plot::pen_number(&palette, stroke.color) - 1)
+                % self.pens + 1;
+
+            if number != pen {
+                writeln!(out, "SP{};", number).unwrap();
+
+                pen = number;
+            }
+
+            let start = stroke.points[0];
+
+            writeln!(out, "PU{},{};", unit(start.x), unit(start.y)).unwrap();
+
+            let points: Vec<String> = stroke.points[1..]
+                .iter()
+                .map(| point | format!("{},{}", unit(point.x), unit(point.y)))
+                .collect();
+
+            writeln!(out, "PD{};", points.join(",")).unwrap();
+        }
+
+        out.push_str("PU;\nSP0;\n");
+
+        out
+    }
+}
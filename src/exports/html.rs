@@ -0,0 +1,244 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    bounds::Bounds,
+    drawing::{
+        Drawing,
+        Rgb,
+        Shape
+    }
+};
+
+/// Viewer script, drawing `PATHS` on the canvas with pan, zoom
+/// and a slider replaying the draw order
+const SCRIPT: &str = r##"
+const canvas = document.getElementById("figure");
+const slider = document.getElementById("steps");
+const label = document.getElementById("count");
+const ctx = canvas.getContext("2d");
+// Segments, plus one step for each fill and each dot
+const total = PATHS.reduce((n, p) => n + (p[3] === 2 ? 1 : p[2].length / 2 - 1 + p[3]), 0);
+let view = { scale: 1, x: 0, y: 0 };
+
+function fit() {
+    const [left, bottom, right, top] = BOUNDS;
+    const w = Math.max(right - left, 1e-9);
+    const h = Math.max(top - bottom, 1e-9);
+
+    view.scale = 0.9 * Math.min(canvas.width / w, canvas.height / h);
+    view.x = canvas.width / 2 - (left + w / 2) * view.scale;
+    view.y = canvas.height / 2 + (bottom + h / 2) * view.scale;
+}
+
+function draw() {
+    let left = Number(slider.value);
+
+    label.textContent = left + " / " + total;
+    ctx.setTransform(1, 0, 0, 1, 0, 0);
+    ctx.fillStyle = BACKGROUND || "#ffffff";
+    ctx.fillRect(0, 0, canvas.width, canvas.height);
+    ctx.setTransform(view.scale, 0, 0, -view.scale, view.x, view.y);
+    ctx.lineCap = "round";
+    ctx.lineJoin = "round";
+
+    for (const [color, width, points, kind] of PATHS) {
+        if (left <= 0) {
+            break;
+        }
+
+        ctx.strokeStyle = COLORS[color];
+        ctx.fillStyle = COLORS[color];
+        ctx.lineWidth = width;
+
+        if (kind === 2) {
+            ctx.beginPath();
+            ctx.arc(points[0], points[1], width / 2, 0, 2 * Math.PI);
+            ctx.fill();
+            left -= 1;
+            continue;
+        }
+
+        const segments = Math.min(points.length / 2 - 1, left);
+
+        ctx.beginPath();
+        ctx.moveTo(points[0], points[1]);
+
+        for (let i = 1; i <= segments; i++) {
+            ctx.lineTo(points[2 * i], points[2 * i + 1]);
+        }
+
+        if (kind === 1 && segments === points.length / 2 - 1) {
+            ctx.closePath();
+            ctx.fill();
+            left -= 1;
+        }
+
+        ctx.stroke();
+        left -= segments;
+    }
+}
+
+let drag = null;
+
+canvas.addEventListener("mousedown", (e) => { drag = [e.offsetX, e.offsetY]; });
+window.addEventListener("mouseup", () => { drag = null; });
+canvas.addEventListener("mousemove", (e) => {
+    if (drag) {
+        view.x += e.offsetX - drag[0];
+        view.y += e.offsetY - drag[1];
+        drag = [e.offsetX, e.offsetY];
+        draw();
+    }
+});
+canvas.addEventListener("wheel", (e) => {
+    e.preventDefault();
+    const factor = e.deltaY < 0 ? 1.1 : 1 / 1.1;
+
+    view.x = e.offsetX - (e.offsetX - view.x) * factor;
+    view.y = e.offsetY - (e.offsetY - view.y) * factor;
+    view.scale *= factor;
+    draw();
+});
+canvas.addEventListener("dblclick", () => { fit(); draw(); });
+slider.max = total;
+slider.value = total;
+slider.addEventListener("input", draw);
+fit();
+draw();
+"##;
+
+/// Self-contained HTML page drawing the figure on a `<canvas>`
+pub struct Html {
+    /// Page title
+    pub title: String,
+    /// Canvas width in pixels
+    pub width: u32,
+    /// Canvas height in pixels
+    pub height: u32
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self {
+            title: String::from("Lindenmayer system"),
+            width: 800,
+            height: 600
+        }
+    }
+}
+
+impl Html {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page title
+    pub fn set_title(&mut self, title: &str) -> &mut Self {
+        self.title = String::from(title);
+
+        self
+    }
+
+    /// Set the canvas size in pixels
+    pub fn set_size(&mut self, width: u32, height: u32) -> &mut Self {
+        self.width = width;
+        self.height = height;
+
+        self
+    }
+}
+
+/// Escape the HTML special characters of `value`
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Export for Html {
+    fn export(&self, drawing: &Drawing) -> String {
+        let mut colors: Vec<Rgb> = Vec::new();
+        let mut paths: Vec<String> = Vec::new();
+
+        for element in &drawing.elements {
+            let color = element.style.color;
+
+            let index = match colors.iter().position(| c | *c == color) {
+                Some(index) => index,
+                None => {
+                    colors.push(color);
+                    colors.len() - 1
+                }
+            };
+
+            let kind = match element.shape {
+                Shape::Polyline(_) => 0,
+                Shape::Polygon(_) => 1,
+                Shape::Point(_) => 2
+            };
+
+            let points: Vec<String> = element.shape
+                .points()
+                .iter()
+                .map(| p | format!("{:.3},{:.3}", p.x, p.y))
+                .collect();
+
+            paths.push(format!(
+                "[{},{},[{}],{}]",
+                index,
+                element.style.width,
+                points.join(","),
+                kind
+            ));
+        }
+
+        let colors: Vec<String> = colors
+            .iter()
+            .map(| c | format!("\"{}\"", c.hex()))
+            .collect();
+
+        let bounds = Bounds::from(drawing);
+        let (min, max) = (bounds.min(), bounds.max());
+        let bounds = format!(
+            "[{:.3},{:.3},{:.3},{:.3}]",
+            min.x, min.y, max.x, max.y
+        );
+
+        let background = match drawing.background {
+            Some(color) => format!("\"{}\"", color.hex()),
+            None => String::from("null")
+        };
+
+        let title = escape(&self.title);
+        let mut out = String::new();
+
+        writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(out, "<title>{}</title>", title).unwrap();
+        writeln!(out, "<style>body {{ font-family: sans-serif; }} canvas {{ border: 1px solid #888; cursor: grab; }}</style>").unwrap();
+        writeln!(out, "</head>\n<body>\n<h1>{}</h1>", title).unwrap();
+        writeln!(
+            out,
+            "<canvas id=\"figure\" width=\"{}\" height=\"{}\"></canvas>",
+            self.width,
+            self.height
+        ).unwrap();
+        writeln!(
+            out,
+            "<p><input id=\"steps\" type=\"range\" min=\"0\" style=\"width: {}px\"> <span id=\"count\"></span></p>",
+            self.width
+        ).unwrap();
+        writeln!(out, "<p>Drag to pan, scroll to zoom, double click to fit.</p>").unwrap();
+        writeln!(out, "<script>").unwrap();
+        writeln!(out, "const COLORS = [{}];", colors.join(",")).unwrap();
+        writeln!(out, "const PATHS = [{}];", paths.join(",\n")).unwrap();
+        writeln!(out, "const BOUNDS = {};", bounds).unwrap();
+        writeln!(out, "const BACKGROUND = {};", background).unwrap();
+        out.push_str(SCRIPT);
+        writeln!(out, "</script>\n</body>\n</html>").unwrap();
+
+        out
+    }
+}
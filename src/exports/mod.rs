@@ -0,0 +1,312 @@
+/// Scalable Vector Graphics
+pub mod svg;
+/// Stroke ordering for pen plotters
+pub mod plot;
+/// G-code for pen plotters
+pub mod gcode;
+/// HP-GL for plotters and vinyl cutters
+pub mod hpgl;
+/// AutoCAD DXF for laser cutters and CAD
+pub mod dxf;
+/// Portable Document Format
+pub mod pdf;
+/// Encapsulated PostScript
+pub mod eps;
+/// TikZ picture for LaTeX documents
+pub mod tikz;
+/// Standalone interactive HTML page
+pub mod html;
+/// Unicode text preview for terminals
+pub mod terminal;
+
+use crate::{
+    models::export_model::Export,
+    bounds::Bounds,
+    drawing::Drawing,
+    state::{
+        FitMode,
+        Pos,
+        Size,
+        ScreenPosition,
+        SizeType
+    }
+};
+
+/// Output formats, chosen by name or by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Svg,
+    GCode,
+    Hpgl,
+    Dxf,
+    Pdf,
+    Eps,
+    Tikz,
+    Html,
+    /// Terminal preview
+    Text
+}
+
+impl Format {
+    /// Find a format by its name or its usual file extension
+    pub fn from_name(name: &str) -> Option<Self> {
+        let format = match name.to_lowercase().as_str() {
+            "svg" => Format::Svg,
+            "gcode" | "nc" | "gc" => Format::GCode,
+            "hpgl" | "plt" => Format::Hpgl,
+            "dxf" => Format::Dxf,
+            "pdf" => Format::Pdf,
+            "eps" | "ps" => Format::Eps,
+            "tikz" | "tex" => Format::Tikz,
+            "html" | "htm" => Format::Html,
+            "txt" | "text" => Format::Text,
+            _ => return None
+        };
+
+        Some(format)
+    }
+
+    /// Find a format from the extension of `filename`
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        std::path::Path::new(filename)
+            .extension()
+            .and_then(| ext | ext.to_str())
+            .and_then(Self::from_name)
+    }
+
+    /// Usual file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::GCode => "gcode",
+            Format::Hpgl => "hpgl",
+            Format::Dxf => "dxf",
+            Format::Pdf => "pdf",
+            Format::Eps => "eps",
+            Format::Tikz => "tex",
+            Format::Html => "html",
+            Format::Text => "txt"
+        }
+    }
+
+    /// Exporter with its default settings, `size` is the canvas size in
+    /// the format unit: pixels for SVG and HTML, millimeters for the
+    /// plotters and the pages, centimeters (width only) for TikZ and
+    /// characters for the text
+    pub fn exporter(&self, size: Option<(f64, f64)>) -> Box<dyn Export> {
+        match self {
+            Format::Svg => Box::new(svg::Svg::new(match size {
+                Some((w, h)) => SizeType::Fit(w, h, FitMode::Contain),
+                None => SizeType::Auto
+            })),
+            Format::GCode => {
+                let (w, h) = size.unwrap_or(PageSize::A4.mm());
+
+                Box::new(gcode::GCode::new(w, h))
+            },
+            Format::Hpgl => {
+                let (w, h) = size.unwrap_or(PageSize::A4.mm());
+
+                Box::new(hpgl::Hpgl::new(w, h))
+            },
+            Format::Dxf => {
+                let (w, h) = size.unwrap_or(PageSize::A4.mm());
+
+                Box::new(dxf::Dxf::new(w, h))
+            },
+            Format::Pdf => Box::new(pdf::Pdf::new(match size {
+                Some((w, h)) => PageSize::Custom(w, h),
+                None => PageSize::A4
+            })),
+            Format::Eps => Box::new(eps::Eps::new(match size {
+                Some((w, h)) => PageSize::Custom(w, h),
+                None => PageSize::A4
+            })),
+            Format::Tikz => {
+                let mut tikz = tikz::Tikz::new();
+
+                if let Some((w, _)) = size {
+                    tikz.set_width(w);
+                }
+
+                Box::new(tikz)
+            },
+            Format::Html => {
+                let mut html = html::Html::new();
+
+                if let Some((w, h)) = size {
+                    html.set_size(w as u32, h as u32);
+                }
+
+                Box::new(html)
+            },
+            Format::Text => Box::new(match size {
+                Some((w, h)) => terminal::Terminal::new(w as usize, h as usize),
+                None => terminal::Terminal::default()
+            })
+        }
+    }
+}
+
+/// PostScript points per millimeter
+pub const POINTS_PER_MM: f64 = 72. / 25.4;
+
+/// Physical page size
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PageSize {
+    A3,
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+    /// Width and height in mm
+    Custom(f64, f64)
+}
+
+impl PageSize {
+    /// Portrait width and height in mm
+    pub fn mm(&self) -> (f64, f64) {
+        match self {
+            PageSize::A3 => (297., 420.),
+            PageSize::A4 => (210., 297.),
+            PageSize::A5 => (148., 210.),
+            PageSize::Letter => (215.9, 279.4),
+            PageSize::Legal => (215.9, 355.6),
+            PageSize::Custom(w, h) => (*w, *h)
+        }
+    }
+
+    /// Width and height in PostScript points
+    pub fn points(&self) -> (f64, f64) {
+        let (w, h) = self.mm();
+
+        (w * POINTS_PER_MM, h * POINTS_PER_MM)
+    }
+
+    /// Same size with the width and the height swapped
+    pub fn landscape(&self) -> Self {
+        let (w, h) = self.mm();
+
+        PageSize::Custom(h, w)
+    }
+}
+
+/// Placement of a drawing on an output canvas
+///
+/// The canvas coordinates have their origin at the top left corner
+/// and the y axis pointing down
+pub struct Frame {
+    /// Canvas width
+    pub width: f64,
+    /// Canvas height
+    pub height: f64,
+    /// Figure left side in turtle units
+    left: f64,
+    /// Figure top side in turtle units
+    top: f64,
+    /// Figure top left corner on the canvas
+    origin: (f64, f64),
+    /// Horizontal and vertical canvas units per turtle unit
+    scale: (f64, f64)
+}
+
+impl Frame {
+    /// Place the figure on the canvas
+    ///
+    /// The `margin` is in turtle units, or in canvas units
+    /// for `SizeType::Fit` since the figure is scaled
+    pub fn new(
+        drawing: &Drawing,
+        size: SizeType,
+        pos: ScreenPosition,
+        margin: f64
+    ) -> Self {
+        if let SizeType::Fit(w, h, mode) = size {
+            return Self::fit_with(drawing, w, h, margin, mode, pos);
+        }
+
+        let mut bounds = Bounds::from(drawing);
+
+        bounds.set_margin(margin);
+
+        let figure = bounds.size();
+        let canvas = size.canvas(&figure);
+
+        Self {
+            width: canvas.w,
+            height: canvas.h,
+            left: bounds.min().x,
+            top: bounds.max().y,
+            origin: pos.origin(&canvas, &figure),
+            scale: (1., 1.)
+        }
+    }
+
+    /// Scale the figure to fit a `width` x `height` canvas,
+    /// keeping its aspect ratio and centering it inside `margin`
+    pub fn fit(
+        drawing: &Drawing,
+        width: f64,
+        height: f64,
+        margin: f64
+    ) -> Self {
+        Self::fit_with(
+            drawing,
+            width,
+            height,
+            margin,
+            FitMode::Contain,
+            ScreenPosition::Center
+        )
+    }
+
+    /// Scale the figure with `mode` into a `width` x `height` canvas,
+    /// then place it with `pos` inside `margin`
+    pub fn fit_with(
+        drawing: &Drawing,
+        width: f64,
+        height: f64,
+        margin: f64,
+        mode: FitMode,
+        pos: ScreenPosition
+    ) -> Self {
+        let bounds = Bounds::from(drawing);
+        let figure = bounds.size();
+
+        let area = Size {
+            w: (width - 2. * margin).max(0.),
+            h: (height - 2. * margin).max(0.)
+        };
+
+        let scale = mode.scale(&area, &figure);
+        let scaled = Size {
+            w: figure.w * scale.0,
+            h: figure.h * scale.1
+        };
+
+        let (x, y) = pos.origin(&area, &scaled);
+
+        Self {
+            width,
+            height,
+            left: bounds.min().x,
+            top: bounds.max().y,
+            origin: (x + margin, y + margin),
+            scale
+        }
+    }
+
+    /// Canvas units per turtle unit, used for the pen sizes
+    pub fn scale(&self) -> f64 {
+        (self.scale.0 * self.scale.1).sqrt()
+    }
+
+    /// Convert a turtle position into canvas coordinates
+    pub fn map(&self, point: Pos) -> (f64, f64) {
+        (
+            self.origin.0 + (point.x - self.left) * self.scale.0,
+            self.origin.1 + (self.top - point.y) * self.scale.1
+        )
+    }
+}
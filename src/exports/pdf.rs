@@ -0,0 +1,170 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::{Export, ExportPages},
+    drawing::{
+        Drawing,
+        Rgb,
+        Shape
+    },
+    exports::{
+        Frame,
+        PageSize,
+        POINTS_PER_MM
+    }
+};
+
+/// Vector PDF writer
+///
+/// Every drawing is scaled to fit its page inside the margins
+pub struct Pdf {
+    /// Physical page size
+    pub page: PageSize,
+    /// Blank space around the figure in mm
+    pub margin: f64
+}
+
+impl Pdf {
+    pub fn new(page: PageSize) -> Self {
+        Self {
+            page,
+            margin: 10.
+        }
+    }
+
+    /// Set the blank space around the figure in mm
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Page content stream drawing `drawing`
+    fn content(&self, drawing: &Drawing) -> String {
+        let (w, h) = self.page.points();
+        let frame = Frame::fit(drawing, w, h, self.margin * POINTS_PER_MM);
+        let color = | c: Rgb | format!(
+            "{:.3} {:.3} {:.3}",
+            c.r / 255.,
+            c.g / 255.,
+            c.b / 255.
+        );
+
+        let mut out = String::new();
+
+        if let Some(bg) = drawing.background {
+            writeln!(out, "{} rg 0 0 {:.3} {:.3} re f", color(bg), w, h).unwrap();
+        }
+
+        // Round caps and joins
+        out.push_str("1 J 1 j\n");
+
+        for element in &drawing.elements {
+            let stroke = color(element.style.color);
+
+            writeln!(
+                out,
+                "{} RG {:.3} w",
+                stroke,
+                element.style.width * frame.scale()
+            ).unwrap();
+
+            let points = element.shape.points();
+
+            for (i, point) in points.iter().enumerate() {
+                let (x, y) = frame.map(*point);
+                let op = if i == 0 { "m" } else { "l" };
+
+                writeln!(out, "{:.3} {:.3} {}", x, h - y, op).unwrap();
+            }
+
+            match element.shape {
+                Shape::Polyline(_) => out.push_str("S\n"),
+                Shape::Polygon(_) => writeln!(out, "{} rg h B", stroke).unwrap(),
+                Shape::Point(point) => {
+                    // Zero length line, drawn as a dot by the round cap
+                    let (x, y) = frame.map(point);
+
+                    writeln!(out, "{:.3} {:.3} l S", x, h - y).unwrap();
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for Pdf {
+    fn default() -> Self {
+        Self::new(PageSize::default())
+    }
+}
+
+impl Export for Pdf {
+    fn export(&self, drawing: &Drawing) -> String {
+        self.export_pages(std::slice::from_ref(drawing))
+    }
+}
+
+impl ExportPages for Pdf {
+    fn export_pages(&self, drawings: &[Drawing]) -> String {
+        let (w, h) = self.page.points();
+        let mut objects: Vec<String> = Vec::new();
+
+        // Catalog and page tree, each page uses two objects
+        let kids: Vec<String> = (0..drawings.len())
+            .map(| i | format!("{} 0 R", 3 + i * 2))
+            .collect();
+
+        objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+        objects.push(format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            drawings.len()
+        ));
+
+        for (i, drawing) in drawings.iter().enumerate() {
+            let content = self.content(drawing);
+
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /Contents {} 0 R >>",
+                w,
+                h,
+                4 + i * 2
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+
+        // Body, then the cross reference table with the byte offsets
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+
+            writeln!(out, "{} 0 obj\n{}\nendobj", i + 1, object).unwrap();
+        }
+
+        let xref = out.len();
+
+        writeln!(out, "xref\n0 {}", objects.len() + 1).unwrap();
+        out.push_str("0000000000 65535 f \n");
+
+        for offset in offsets {
+            writeln!(out, "{:010} 00000 n ", offset).unwrap();
+        }
+
+        writeln!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+            objects.len() + 1,
+            xref
+        ).unwrap();
+
+        out
+    }
+}
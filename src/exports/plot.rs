@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use crate::{
+    drawing::{
+        Drawing,
+        Rgb,
+        Shape
+    },
+    exports::Frame,
+    state::Pos
+};
+
+/// Tolerance used to compare positions
+const EPSILON: f64 = 1e-6;
+
+/// Path followed by a pen while it is down
+#[derive(Debug, Clone)]
+pub struct Stroke {
+    pub points: Vec<Pos>,
+    /// Pen color
+    pub color: Rgb
+}
+
+impl Stroke {
+    fn start(&self) -> Pos {
+        self.points[0]
+    }
+
+    fn end(&self) -> Pos {
+        self.points[self.points.len() - 1]
+    }
+}
+
+fn distance(a: Pos, b: Pos) -> f64 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+/// Squared distance, enough to compare distances
+fn distance2(a: Pos, b: Pos) -> f64 {
+    (b.x - a.x).powi(2) + (b.y - a.y).powi(2)
+}
+
+fn same(a: Pos, b: Pos) -> bool {
+    distance(a, b) <= EPSILON
+}
+
+/// Convert the drawing elements into strokes, in draw order
+///
+/// Polygons are closed and points become single position strokes
+pub fn strokes(drawing: &Drawing) -> Vec<Stroke> {
+    drawing.elements
+        .iter()
+        .map(| element | {
+            let mut points = element.shape.points().to_vec();
+
+            if let Shape::Polygon(_) = element.shape {
+                points.push(points[0]);
+            }
+
+            Stroke {
+                points,
+                color: element.style.color
+            }
+        })
+        .filter(| stroke | !stroke.points.is_empty())
+        .collect()
+}
+
+/// Remove the points standing in the middle of a straight line,
+/// e.g the ones left by successive `Do::Forward`
+pub fn merge_collinear(points: &mut Vec<Pos>) {
+    let mut merged: Vec<Pos> = Vec::with_capacity(points.len());
+
+    for point in points.drain(..) {
+        if let Some(&last) = merged.last() {
+            if same(last, point) {
+                continue;
+            }
+        }
+
+        if merged.len() >= 2 {
+            let a = merged[merged.len() - 2];
+            let b = merged[merged.len() - 1];
+
+            let (ux, uy) = (b.x - a.x, b.y - a.y);
+            let (vx, vy) = (point.x - b.x, point.y - b.y);
+
+            let cross = ux * vy - uy * vx;
+            let dot = ux * vx + uy * vy;
+
+            // Same direction, the middle point is useless
+            if cross.abs() <= EPSILON * distance(a, b) * distance(b, point)
+                && dot > 0. {
+                merged.pop();
+            }
+        }
+
+        merged.push(point);
+    }
+
+    *points = merged;
+}
+
+/// Ramer-Douglas-Peucker simplification, drop the points closer
+/// than `tolerance` from the simplified line
+pub fn simplify(points: &[Pos], tolerance: f64) -> Vec<Pos> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let length = distance(first, last);
+
+    // Farthest point from the line between both ends
+    let mut farthest = 0;
+    let mut max = 0.;
+
+    for (i, point) in points.iter().enumerate().take(points.len() - 1).skip(1) {
+        let d = if length <= EPSILON {
+            distance(first, *point)
+        } else {
+            ((last.x - first.x) * (first.y - point.y)
+                - (first.x - point.x) * (last.y - first.y)).abs() / length
+        };
+
+        if d > max {
+            farthest = i;
+            max = d;
+        }
+    }
+
+    if max <= tolerance {
+        return vec![first, last];
+    }
+
+    let mut left = simplify(&points[..=farthest], tolerance);
+    let right = simplify(&points[farthest..], tolerance);
+
+    left.pop();
+    left.extend(right);
+
+    left
+}
+
+/// Above this amount of strokes of a color, they are kept in draw order
+pub const ORDER_LIMIT: usize = 1_000_000;
+
+/// Stroke ends bucketed in a uniform grid, to find the nearest one
+/// without looking at every stroke
+struct Ends {
+    min: Pos,
+    cell: f64,
+    cols: usize,
+    rows: usize,
+    /// Position, stroke index and whether it is the stroke end
+    cells: Vec<Vec<(Pos, usize, bool)>>
+}
+
+impl Ends {
+    fn new(strokes: &[Stroke]) -> Self {
+        let mut min = Pos { x: f64::INFINITY, y: f64::INFINITY };
+        let mut max = Pos { x: f64::NEG_INFINITY, y: f64::NEG_INFINITY };
+
+        for point in strokes.iter().flat_map(| s | [s.start(), s.end()]) {
+            min = Pos { x: min.x.min(point.x), y: min.y.min(point.y) };
+            max = Pos { x: max.x.max(point.x), y: max.y.max(point.y) };
+        }
+
+        // About one stroke per cell
+        let (w, h) = (max.x - min.x, max.y - min.y);
+        let n = strokes.len() as f64;
+        let mut cell = (w * h / n).sqrt();
+
+        if cell <= EPSILON {
+            cell = w.max(h) / n;
+        }
+        if cell <= EPSILON {
+            cell = 1.;
+        }
+
+        let cols = (w / cell) as usize + 1;
+        let rows = (h / cell) as usize + 1;
+
+        let mut ends = Self {
+            min,
+            cell,
+            cols,
+            rows,
+            cells: vec![Vec::new(); cols * rows]
+        };
+
+        for (i, stroke) in strokes.iter().enumerate() {
+            for (point, reverse) in [(stroke.start(), false), (stroke.end(), true)] {
+                let (x, y) = ends.coords(point);
+
+                ends.cells[y * cols + x].push((point, i, reverse));
+            }
+        }
+
+        ends
+    }
+
+    /// Cell containing `point`, the closest one if it is outside
+    fn coords(&self, point: Pos) -> (usize, usize) {
+        let x = ((point.x - self.min.x) / self.cell).max(0.) as usize;
+        let y = ((point.y - self.min.y) / self.cell).max(0.) as usize;
+
+        (x.min(self.cols - 1), y.min(self.rows - 1))
+    }
+
+    /// Nearest end of a stroke not drawn yet, searching the cells
+    /// ring by ring around `position`
+    fn nearest(&mut self, position: Pos, drawn: &[bool]) -> Option<(usize, bool)> {
+        let (cx, cy) = self.coords(position);
+        let (cx, cy) = (cx as isize, cy as isize);
+        let (cols, rows) = (self.cols as isize, self.rows as isize);
+
+        let mut best = None;
+        let mut best_distance = f64::INFINITY;
+
+        for ring in 0..cols.max(rows) {
+            for y in (cy - ring)..=(cy + ring) {
+                if y < 0 || y >= rows {
+                    continue;
+                }
+
+                // Only the border of the ring, unless on its first or last row
+                let step = if y == cy - ring || y == cy + ring {
+                    1
+                } else {
+                    (2 * ring).max(1)
+                };
+
+                for x in ((cx - ring)..=(cx + ring)).step_by(step as usize) {
+                    if x < 0 || x >= cols {
+                        continue;
+                    }
+
+                    let cell = &mut self.cells[(y * cols + x) as usize];
+
+                    cell.retain(| &(_, i, _) | !drawn[i]);
+
+                    for &(point, i, reverse) in cell.iter() {
+                        let d = distance2(position, point);
+
+                        // Ties go to the last drawn stroke, then to its end
+                        if d < best_distance
+                            || (d == best_distance && Some((i, reverse)) > best) {
+                            best = Some((i, reverse));
+                            best_distance = d;
+                        }
+                    }
+                }
+            }
+
+            // The next ring is at least this far away
+            let reach = ring as f64 * self.cell;
+
+            if best.is_some() && best_distance <= reach * reach {
+                break;
+            }
+        }
+
+        best
+    }
+}
+
+/// Reorder and reverse the strokes to minimize the pen-up travel,
+/// then join the strokes that continue each other
+///
+/// Strokes are grouped by color (in order of appearance) so each
+/// pen is only used once, the travel starts at `start`. A group of
+/// more than `ORDER_LIMIT` strokes keeps its order
+pub fn optimize(strokes: Vec<Stroke>, start: Pos) -> Vec<Stroke> {
+    let mut groups: Vec<(Rgb, Vec<Stroke>)> = Vec::new();
+    let mut indexes: HashMap<[u64; 3], usize> = HashMap::new();
+
+    for stroke in strokes {
+        let color = stroke.color;
+        let key = [color.r.to_bits(), color.g.to_bits(), color.b.to_bits()];
+
+        match indexes.get(&key) {
+            Some(&index) => groups[index].1.push(stroke),
+            None => {
+                indexes.insert(key, groups.len());
+                groups.push((color, vec![stroke]));
+            }
+        }
+    }
+
+    let mut ordered: Vec<Stroke> = Vec::new();
+    let mut position = start;
+
+    for (_, group) in groups {
+        let sorted = if group.len() > ORDER_LIMIT {
+            group
+        } else {
+            nearest_order(group, position)
+        };
+
+        for mut stroke in sorted {
+            position = stroke.end();
+
+            // Continuing the previous stroke without lifting the pen
+            match ordered.last_mut() {
+                Some(last) if last.color == stroke.color
+                    && same(last.end(), stroke.start()) => {
+                    last.points.extend(stroke.points.drain(1..));
+                },
+                _ => ordered.push(stroke)
+            }
+        }
+    }
+
+    for stroke in ordered.iter_mut() {
+        merge_collinear(&mut stroke.points);
+    }
+
+    ordered
+}
+
+/// Greedy nearest neighbour from `position`, considering both stroke ends
+fn nearest_order(group: Vec<Stroke>, mut position: Pos) -> Vec<Stroke> {
+    let mut ends = Ends::new(&group);
+    let mut drawn = vec![false; group.len()];
+    let mut group: Vec<Option<Stroke>> = group.into_iter().map(Some).collect();
+    let mut sorted = Vec::with_capacity(group.len());
+
+    while let Some((i, reverse)) = ends.nearest(position, &drawn) {
+        let mut stroke = match group[i].take() {
+            Some(stroke) => stroke,
+            None => break
+        };
+
+        if reverse {
+            stroke.points.reverse();
+        }
+
+        drawn[i] = true;
+        position = stroke.end();
+        sorted.push(stroke);
+    }
+
+    sorted
+}
+
+/// Total pen-up travel distance needed to draw `strokes` from `start`
+pub fn travel(strokes: &[Stroke], start: Pos) -> f64 {
+    let mut position = start;
+    let mut total = 0.;
+
+    for stroke in strokes {
+        total += distance(position, stroke.start());
+        position = stroke.end();
+    }
+
+    total
+}
+
+/// Strokes scaled to fit a `width` x `height` mm sheet inside `margin`,
+/// with the origin at the bottom left corner and the y axis pointing up,
+/// then optimized for a pen starting at the origin
+pub fn sheet_strokes(
+    drawing: &Drawing,
+    width: f64,
+    height: f64,
+    margin: f64
+) -> Vec<Stroke> {
+    let frame = Frame::fit(drawing, width, height, margin);
+
+    let strokes = strokes(drawing)
+        .into_iter()
+        .map(| mut stroke | {
+            for point in stroke.points.iter_mut() {
+                let (x, y) = frame.map(*point);
+
+                *point = Pos {
+                    x,
+                    y: height - y
+                };
+            }
+
+            stroke
+        })
+        .collect();
+
+    optimize(strokes, Pos::default())
+}
+
+/// Distinct pen colors in order of appearance, the pen number
+/// of a color is its index + 1
+pub fn palette(strokes: &[Stroke]) -> Vec<Rgb> {
+    let mut colors: Vec<Rgb> = Vec::new();
+
+    for stroke in strokes {
+        if !colors.contains(&stroke.color) {
+            colors.push(stroke.color);
+        }
+    }
+
+    colors
+}
+
+/// Pen number of `color` in `palette`
+pub fn pen_number(palette: &[Rgb], color: Rgb) -> usize {
+    palette
+        .iter()
+        .position(| c | *c == color)
+        .unwrap_or(0) + 1
+}
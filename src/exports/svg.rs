@@ -0,0 +1,118 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    drawing::{
+        Drawing,
+        Shape
+    },
+    exports::Frame,
+    state::{
+        ScreenPosition,
+        SizeType
+    }
+};
+
+/// SVG writer, the canvas unit is the turtle unit
+pub struct Svg {
+    /// SVG size type
+    pub size: SizeType,
+    position: ScreenPosition,
+    /// Blank space around the figure, in turtle units or
+    /// in SVG units for `SizeType::Fit`
+    pub margin: f64
+}
+
+impl Svg {
+    pub fn new(size_type: SizeType) -> Self {
+        Self {
+            size: size_type,
+            position: ScreenPosition::default(),
+            margin: 0.
+        }
+    }
+
+    /// Set the figure position on the canvas
+    pub fn set_figure_pos(&mut self, pos: ScreenPosition) -> &mut Self {
+        self.position = pos;
+
+        self
+    }
+
+    /// Set the blank space around the figure
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin;
+
+        self
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self::new(SizeType::Auto)
+    }
+}
+
+impl Export for Svg {
+    fn export(&self, drawing: &Drawing) -> String {
+        let frame = Frame::new(drawing, self.size, self.position, self.margin);
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.3}\" height=\"{h:.3}\" viewBox=\"0 0 {w:.3} {h:.3}\">",
+            w = frame.width,
+            h = frame.height
+        ).unwrap();
+
+        if let Some(color) = drawing.background {
+            writeln!(
+                out,
+                "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+                color.hex()
+            ).unwrap();
+        }
+
+        for element in &drawing.elements {
+            let points: Vec<String> = element.shape
+                .points()
+                .iter()
+                .map(| point | {
+                    let (x, y) = frame.map(*point);
+
+                    format!("{:.3},{:.3}", x, y)
+                })
+                .collect();
+
+            let color = element.style.color.hex();
+            let width = element.style.width * frame.scale();
+            let depth = element.depth;
+
+            match &element.shape {
+                Shape::Polyline(_) => writeln!(
+                    out,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.3}\" stroke-linecap=\"round\" stroke-linejoin=\"round\" data-depth=\"{}\"/>",
+                    points.join(" "), color, width, depth
+                ),
+                Shape::Polygon(_) => writeln!(
+                    out,
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{:.3}\" data-depth=\"{}\"/>",
+                    points.join(" "), color, color, width, depth
+                ),
+                Shape::Point(point) => {
+                    let (x, y) = frame.map(*point);
+
+                    writeln!(
+                        out,
+                        "<circle cx=\"{:.3}\" cy=\"{:.3}\" r=\"{:.3}\" fill=\"{}\" data-depth=\"{}\"/>",
+                        x, y, width / 2., color, depth
+                    )
+                }
+            }.unwrap();
+        }
+
+        out.push_str("</svg>\n");
+
+        out
+    }
+}
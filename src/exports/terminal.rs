@@ -0,0 +1,221 @@
+use crate::{
+    models::export_model::Export,
+    drawing::{
+        Drawing,
+        Rgb
+    },
+    exports::{
+        Frame,
+        plot
+    }
+};
+
+/// Characters used to rasterize the figure
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Glyphs {
+    /// 2 x 4 dots per character
+    #[default]
+    Braille,
+    /// 1 x 2 pixels per character
+    HalfBlock
+}
+
+impl Glyphs {
+    /// Pixels per character horizontally and vertically
+    fn cell(&self) -> (usize, usize) {
+        match self {
+            Glyphs::Braille => (2, 4),
+            Glyphs::HalfBlock => (1, 2)
+        }
+    }
+}
+
+/// Braille dot bits, indexed by row then column
+const BRAILLE_DOTS: [[u32; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80]
+];
+
+/// Text preview writer, rasterizing the figure in Unicode characters
+#[derive(Debug, Clone, Copy)]
+pub struct Terminal {
+    /// Width in characters
+    pub columns: usize,
+    /// Height in characters
+    pub rows: usize,
+    pub glyphs: Glyphs,
+    /// 24-bit ANSI colors from the pen colors
+    pub color: bool
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Self::new(80, 24)
+    }
+}
+
+impl Terminal {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Self {
+            columns,
+            rows,
+            glyphs: Glyphs::default(),
+            color: false
+        }
+    }
+
+    /// Set the characters used to rasterize the figure
+    pub fn set_glyphs(&mut self, glyphs: Glyphs) -> &mut Self {
+        self.glyphs = glyphs;
+
+        self
+    }
+
+    /// Enable the 24-bit ANSI colors
+    pub fn set_color(&mut self, color: bool) -> &mut Self {
+        self.color = color;
+
+        self
+    }
+
+    /// Rasterize `drawing`, each pixel holds the color of the last
+    /// stroke drawn over it
+    fn rasterize(&self, drawing: &Drawing) -> (usize, Vec<Option<Rgb>>) {
+        let (cw, ch) = self.glyphs.cell();
+        let (w, h) = (self.columns * cw, self.rows * ch);
+        let mut pixels = vec![None; w * h];
+
+        if w == 0 || h == 0 {
+            return (w, pixels);
+        }
+
+        // Pixel centers, without the stroke widths
+        let mut flat = drawing.clone();
+
+        for element in flat.elements.iter_mut() {
+            element.style.width = 0.;
+        }
+
+        let frame = Frame::fit(&flat, w as f64 - 1., h as f64 - 1., 0.);
+
+        let mut plot_pixel = | x: f64, y: f64, color: Rgb | {
+            let (x, y) = (x.round(), y.round());
+
+            if x >= 0. && y >= 0. && (x as usize) < w && (y as usize) < h {
+                pixels[y as usize * w + x as usize] = Some(color);
+            }
+        };
+
+        for stroke in plot::strokes(&flat) {
+            let points: Vec<(f64, f64)> = stroke.points
+                .iter()
+                .map(| point | frame.map(*point))
+                .collect();
+
+            if points.len() == 1 {
+                plot_pixel(points[0].0, points[0].1, stroke.color);
+            }
+
+            // Sampling each segment once per pixel
+            for pair in points.windows(2) {
+                let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.);
+
+                for i in 0..=steps as usize {
+                    let t = i as f64 / steps;
+
+                    plot_pixel(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t, stroke.color);
+                }
+            }
+        }
+
+        (w, pixels)
+    }
+
+    /// Foreground and background ANSI escape sequences
+    fn paint(&self, fg: Option<Rgb>, bg: Option<Rgb>) -> String {
+        let mut out = String::new();
+
+        if !self.color {
+            return out;
+        }
+
+        out.push_str("\x1b[0m");
+
+        if let Some(color) = fg {
+            let (r, g, b) = color.bytes();
+
+            out.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+        }
+        if let Some(color) = bg {
+            let (r, g, b) = color.bytes();
+
+            out.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
+        }
+
+        out
+    }
+}
+
+impl Export for Terminal {
+    fn export(&self, drawing: &Drawing) -> String {
+        let (w, pixels) = self.rasterize(drawing);
+        let (cw, ch) = self.glyphs.cell();
+        let pixel = | x: usize, y: usize | pixels[y * w + x];
+        let mut out = String::new();
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let (x, y) = (column * cw, row * ch);
+
+                let (glyph, fg, bg) = match self.glyphs {
+                    Glyphs::Braille => {
+                        let mut bits = 0;
+                        let mut fg = None;
+
+                        for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                            for (dx, dot) in dots.iter().enumerate() {
+                                if let Some(color) = pixel(x + dx, y + dy) {
+                                    bits |= dot;
+                                    fg = Some(color);
+                                }
+                            }
+                        }
+
+                        let glyph = match bits {
+                            0 => ' ',
+                            _ => char::from_u32(0x2800 + bits).unwrap_or(' ')
+                        };
+
+                        (glyph, fg, None)
+                    },
+                    Glyphs::HalfBlock => {
+                        let top = pixel(x, y);
+                        let bottom = pixel(x, y + 1);
+
+                        match (top, bottom) {
+                            (Some(_), Some(_)) if self.color => ('▀', top, bottom),
+                            (Some(_), Some(_)) => ('█', top, None),
+                            (Some(_), None) => ('▀', top, None),
+                            (None, Some(_)) => ('▄', bottom, None),
+                            (None, None) => (' ', None, None)
+                        }
+                    }
+                };
+
+                out.push_str(&self.paint(fg, bg));
+                out.push(glyph);
+            }
+
+            if self.color {
+                out.push_str("\x1b[0m");
+            }
+
+            out.push('\n');
+        }
+
+        out
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Write;
+
+use crate::{
+    models::export_model::Export,
+    bounds::Bounds,
+    drawing::{
+        Drawing,
+        Rgb,
+        Shape
+    },
+    exports::plot
+};
+
+/// TikZ writer, emits a `tikzpicture` environment
+pub struct Tikz {
+    /// Centimeters per turtle unit
+    pub scale: f64,
+    /// Picture width in cm, overrides `scale` when set
+    pub width: Option<f64>,
+    /// Points per turtle unit of pen size
+    pub line_scale: f64,
+    /// Simplification tolerance in cm, disabled when `None`
+    pub tolerance: Option<f64>
+}
+
+impl Default for Tikz {
+    fn default() -> Self {
+        Self {
+            scale: 0.1,
+            width: None,
+            line_scale: 0.4,
+            tolerance: None
+        }
+    }
+}
+
+impl Tikz {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the amount of centimeters per turtle unit
+    pub fn set_scale(&mut self, scale: f64) -> &mut Self {
+        self.scale = scale;
+
+        self
+    }
+
+    /// Scale the figure to `width` centimeters
+    pub fn set_width(&mut self, width: f64) -> &mut Self {
+        self.width = Some(width);
+
+        self
+    }
+
+    /// Set the amount of points per turtle unit of pen size
+    pub fn set_line_scale(&mut self, line_scale: f64) -> &mut Self {
+        self.line_scale = line_scale;
+
+        self
+    }
+
+    /// Drop the points closer than `tolerance` cm from the paths
+    pub fn set_tolerance(&mut self, tolerance: f64) -> &mut Self {
+        self.tolerance = Some(tolerance);
+
+        self
+    }
+
+    /// Centimeters per turtle unit for `drawing`
+    fn unit(&self, drawing: &Drawing) -> f64 {
+        match (self.width, drawing.bounds()) {
+            (Some(width), Some(square)) if square.width() > 0. => {
+                width / square.width()
+            },
+            _ => self.scale
+        }
+    }
+}
+
+impl Export for Tikz {
+    fn export(&self, drawing: &Drawing) -> String {
+        let unit = self.unit(drawing);
+        let mut colors: Vec<Rgb> = Vec::new();
+        let mut paths = String::new();
+
+        for element in &drawing.elements {
+            let color = element.style.color;
+
+            let index = match colors.iter().position(| c | *c == color) {
+                Some(index) => index,
+                None => {
+                    colors.push(color);
+                    colors.len() - 1
+                }
+            };
+
+            // Simplifying in turtle units
+            let points = match (&element.shape, self.tolerance) {
+                (Shape::Polyline(points), Some(tolerance)) => {
+                    plot::simplify(points, tolerance / unit)
+                },
+                _ => element.shape.points().to_vec()
+            };
+
+            let coords: Vec<String> = points
+                .iter()
+                .map(| p | format!("({:.3},{:.3})", p.x * unit, p.y * unit))
+                .collect();
+
+            let width = element.style.width * self.line_scale;
+
+            match element.shape {
+                Shape::Polyline(_) => writeln!(
+                    paths,
+                    "\\draw[color=lcolor{}, line width={:.3}pt] {};",
+                    index, width, coords.join(" -- ")
+                ),
+                Shape::Polygon(_) => writeln!(
+                    paths,
+                    "\\filldraw[color=lcolor{}, line width={:.3}pt] {} -- cycle;",
+                    index, width, coords.join(" -- ")
+                ),
+                Shape::Point(_) => writeln!(
+                    paths,
+                    "\\fill[color=lcolor{}] {} circle ({:.3}pt);",
+                    index, coords[0], width / 2.
+                )
+            }.unwrap();
+        }
+
+        let mut out = String::new();
+
+        for (i, color) in colors.iter().enumerate() {
+            let (r, g, b) = color.bytes();
+
+            writeln!(out, "\\definecolor{{lcolor{}}}{{RGB}}{{{},{},{}}}", i, r, g, b).unwrap();
+        }
+
+        out.push_str("\\begin{tikzpicture}[line cap=round, line join=round]\n");
+
+        let bounds = Bounds::from(drawing);
+
+        if let (Some(bg), false) = (drawing.background, bounds.is_empty()) {
+            let (r, g, b) = bg.bytes();
+            let square = bounds.square();
+
+            writeln!(out, "\\definecolor{{lbackground}}{{RGB}}{{{},{},{}}}", r, g, b).unwrap();
+            writeln!(
+                out,
+                "\\fill[lbackground] ({:.3},{:.3}) rectangle ({:.3},{:.3});",
+                square.top_left.x * unit,
+                square.bottom_right.y * unit,
+                square.bottom_right.x * unit,
+                square.top_left.y * unit
+            ).unwrap();
+        }
+
+        out.push_str(&paths);
+        out.push_str("\\end{tikzpicture}\n");
+
+        out
+    }
+}
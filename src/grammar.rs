@@ -0,0 +1,573 @@
+use std::{
+    error::Error,
+    fmt,
+    fs
+};
+
+use crate::{
+    lindenmayer::{Lindenmayer, DEFAULT_TABLE},
+    models::{
+        rules_model::Rules,
+        action_model::Action
+    },
+    action::Do,
+    renders::renderer::Renderer,
+    rule::Rule,
+    state::{Side, StateFields}
+};
+
+/// Rules of each variable in declaration order
+pub type RulesTable = Vec<(char, Vec<Rule>)>;
+
+/// Error found while reading a grammar, with its line number (from 1,
+/// 0 when the error is not tied to a line)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrammarError {
+    pub line: usize,
+    pub message: String
+}
+
+impl GrammarError {
+    pub fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: String::from(message)
+        }
+    }
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {}: {}", line, self.message)
+        }
+    }
+}
+
+impl Error for GrammarError {}
+
+/// Textual description of a system
+///
+/// One statement per line, the lines starting with `#` are comments:
+///
+/// ```text
+/// axiom +++X
+/// consts +-[]
+/// rule X F+[[X]-X]-F[-FX]+X 2
+/// rule X F-[[X]+X]+F[+FX]-X 1
+/// rule F FF
+/// table winter F F
+/// table winter X X
+/// schedule - - - winter
+/// action F forward 10
+/// action + left 25
+/// action - right 25
+/// action [ save
+/// action ] restore
+/// interpret L [+F-F-F]
+/// decompose B [+X][-X]
+/// age X 1
+/// iterations 6
+/// seed 42
+/// background 0 0 0
+/// width-decay 0.7
+/// restore-fields width color
+/// ```
+///
+/// A rule has an optional weight, several rules for the same
+/// variable are stochastic alternatives. A `table` statement adds a
+/// rule to a named rules table, the tables listed by `schedule` are
+/// used in turn, one per step, `-` standing for the default rules.
+/// An interpretation only replaces its symbol while drawing, a
+/// decomposition replaces its symbol after each step until none is
+/// left. With a `time`, the symbols having an `age` are developed
+/// until this time instead of the iterations
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    pub axiom: String,
+    pub consts: String,
+    /// Rules in declaration order
+    pub rules: RulesTable,
+    /// Named rules tables in declaration order
+    pub tables: Vec<(String, RulesTable)>,
+    /// Table names used in turn, one per step
+    pub schedule: Vec<String>,
+    /// Actions in declaration order
+    pub actions: Vec<(char, Do)>,
+    /// Interpretation rules in declaration order
+    pub interpretations: Vec<(char, String)>,
+    /// Decomposition rules in declaration order
+    pub decompositions: Vec<(char, String)>,
+    pub iterations: usize,
+    /// Terminal ages in declaration order
+    pub ages: Vec<(char, f64)>,
+    /// Growth time replacing the iterations
+    pub time: Option<f64>,
+    pub seed: Option<u64>,
+    pub background: Option<(f64, f64, f64)>,
+    /// Line size factor on each saved state
+    pub width_decay: Option<f64>,
+    /// Parts of the turtle state restored, all by default
+    pub restored_fields: Option<StateFields>
+}
+
+/// Read a single char
+fn symbol(word: &str, line: usize) -> Result<char, GrammarError> {
+    let mut chars = word.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(GrammarError::new(line, &format!("`{}` is not a single symbol", word)))
+    }
+}
+
+/// Read a number
+fn number<T: std::str::FromStr>(word: Option<&&str>, line: usize) -> Result<T, GrammarError> {
+    match word {
+        Some(word) => word
+            .parse()
+            .map_err(| _ | GrammarError::new(line, &format!("`{}` is not a valid number", word))),
+        None => Err(GrammarError::new(line, "missing number"))
+    }
+}
+
+/// Read a rule and its optional weight into `rules`
+fn add_rule(
+    rules: &mut RulesTable,
+    args: &[&str],
+    line: usize
+) -> Result<(), GrammarError> {
+    let src = symbol(args[0], line)?;
+    let weight = match args.get(2) {
+        Some(_) => number(args.get(2), line)?,
+        None => 1
+    };
+    let rule = Rule::new(args[1], weight);
+
+    match rules.iter_mut().find(| (c, _) | *c == src) {
+        Some((_, rules)) => rules.push(rule),
+        None => rules.push((src, vec![rule]))
+    }
+
+    Ok(())
+}
+
+/// Read an action and its arguments, e.g `forward 10`
+pub fn parse_action(words: &[&str], line: usize) -> Result<Do, GrammarError> {
+    let name = match words.first() {
+        Some(name) => *name,
+        None => return Err(GrammarError::new(line, "missing action"))
+    };
+    let args = &words[1..];
+
+    let expected = match name {
+        "save-turn" | "restore-turn" => 2,
+        "pen-color" | "tropism" => 3,
+        "forward" | "backward" | "move" | "left" | "right" | "line-size"
+            | "susceptibility" | "scale-width" | "scale-length" => 1,
+        "pen-up" | "pen-down" | "turn-random" | "color-random"
+            | "save" | "restore" | "swap-turns" | "begin-polygon"
+            | "vertex" | "end-polygon" => 0,
+        other => return Err(GrammarError::new(
+            line,
+            &format!("unknown action `{}`", other)
+        ))
+    };
+
+    if args.len() != expected {
+        return Err(GrammarError::new(
+            line,
+            &format!("`{}` expects {} argument(s)", name, expected)
+        ));
+    }
+
+    let action = match name {
+        "forward" => Do::Forward(number(args.first(), line)?),
+        "backward" => Do::Backward(number(args.first(), line)?),
+        "move" => Do::Move(number(args.first(), line)?),
+        "left" => Do::Left(number(args.first(), line)?),
+        "right" => Do::Right(number(args.first(), line)?),
+        "pen-up" => Do::PenUp,
+        "pen-down" => Do::PenDown,
+        "turn-random" => Do::TurnRandom,
+        "color-random" => Do::ColorRandom,
+        "save" => Do::Save,
+        "restore" => Do::Restore,
+        "line-size" => Do::LineSize(number(args.first(), line)?),
+        "pen-color" => Do::PenColor(
+            number(args.first(), line)?,
+            number(args.get(1), line)?,
+            number(args.get(2), line)?
+        ),
+        "tropism" => Do::Tropism(
+            number(args.first(), line)?,
+            number(args.get(1), line)?,
+            number(args.get(2), line)?
+        ),
+        "susceptibility" => Do::Susceptibility(number(args.first(), line)?),
+        "scale-width" => Do::ScaleWidth(number(args.first(), line)?),
+        "scale-length" => Do::ScaleLength(number(args.first(), line)?),
+        "swap-turns" => Do::SwapTurns,
+        "begin-polygon" => Do::BeginPolygon,
+        "vertex" => Do::Vertex,
+        "end-polygon" => Do::EndPolygon,
+        "save-turn" | "restore-turn" => {
+            let side = match args[0] {
+                "left" => Side::Left,
+                "right" => Side::Right,
+                other => return Err(GrammarError::new(
+                    line,
+                    &format!("`{}` is not a side, use left or right", other)
+                ))
+            };
+            let angle = (side, number(args.get(1), line)?).into();
+
+            if name == "save-turn" {
+                Do::SaveAndTurn(angle)
+            } else {
+                Do::RestoreAndTurn(angle)
+            }
+        },
+        _ => unreachable!()
+    };
+
+    Ok(action)
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a grammar from its textual description
+    pub fn parse(source: &str) -> Result<Self, GrammarError> {
+        let mut grammar = Self::new();
+
+        for (i, raw) in source.lines().enumerate() {
+            grammar.parse_line(raw, i + 1)?;
+        }
+
+        grammar.check()?;
+
+        Ok(grammar)
+    }
+
+    /// Read a grammar from a file
+    pub fn from_file(filename: &str) -> Result<Self, GrammarError> {
+        let source = fs::read_to_string(filename)
+            .map_err(| e | GrammarError::new(0, &format!("{}: {}", filename, e)))?;
+
+        Self::parse(&source)
+    }
+
+    /// Apply a single statement, `line` is used for the errors
+    pub fn parse_line(&mut self, raw: &str, line: usize) -> Result<(), GrammarError> {
+        // Comment lines
+        if raw.trim_start().starts_with('#') {
+            return Ok(());
+        }
+
+        let words: Vec<&str> = raw.split_whitespace().collect();
+
+        let (keyword, args) = match words.split_first() {
+            Some(split) => split,
+            None => return Ok(())
+        };
+
+        match *keyword {
+            "axiom" => self.axiom = args.concat(),
+            "consts" => self.consts.push_str(&args.concat()),
+            "iterations" => self.iterations = number(args.first(), line)?,
+            "restore-fields" => {
+                let mut fields = StateFields::none();
+
+                for field in args.iter().filter(| field | **field != "none") {
+                    match *field {
+                        "tropism" => fields.tropism = true,
+                        "width" => fields.width = true,
+                        "length" => fields.length_scale = true,
+                        "color" => fields.color = true,
+                        "pen" => fields.is_down = true,
+                        other => return Err(GrammarError::new(
+                            line,
+                            &format!(
+                                "unknown field `{}`, use tropism, width, length, color, pen or none",
+                                other
+                            )
+                        ))
+                    }
+                }
+
+                self.restored_fields = Some(fields);
+            },
+            "width-decay" => self.width_decay = Some(number(args.first(), line)?),
+            "time" => self.time = Some(number(args.first(), line)?),
+            "age" => {
+                if args.len() != 2 {
+                    return Err(GrammarError::new(line, "expected `age <symbol> <terminal age>`"));
+                }
+
+                let src = symbol(args[0], line)?;
+                let age: f64 = number(args.get(1), line)?;
+
+                if age <= 0. {
+                    return Err(GrammarError::new(line, "the terminal age must be positive"));
+                }
+
+                self.ages.retain(| (c, _) | *c != src);
+                self.ages.push((src, age));
+            },
+            "seed" => self.seed = Some(number(args.first(), line)?),
+            "background" => self.background = Some((
+                number(args.first(), line)?,
+                number(args.get(1), line)?,
+                number(args.get(2), line)?
+            )),
+            "rule" => {
+                if args.len() < 2 || args.len() > 3 {
+                    return Err(GrammarError::new(line, "expected `rule <symbol> <value> [weight]`"));
+                }
+
+                add_rule(&mut self.rules, args, line)?;
+            },
+            "table" => {
+                if args.len() < 3 || args.len() > 4 {
+                    return Err(GrammarError::new(
+                        line,
+                        "expected `table <name> <symbol> <value> [weight]`"
+                    ));
+                }
+
+                let name = String::from(args[0]);
+                let index = match self.tables.iter().position(| (n, _) | *n == name) {
+                    Some(index) => index,
+                    None => {
+                        self.tables.push((name, Vec::new()));
+                        self.tables.len() - 1
+                    }
+                };
+
+                add_rule(&mut self.tables[index].1, &args[1..], line)?;
+            },
+            "schedule" => {
+                if args.is_empty() {
+                    return Err(GrammarError::new(line, "expected `schedule <table> [table..]`"));
+                }
+
+                self.schedule = args
+                    .iter()
+                    .map(| name | String::from(*name))
+                    .collect();
+            },
+            "interpret" | "decompose" => {
+                if args.len() != 2 {
+                    return Err(GrammarError::new(
+                        line,
+                        &format!("expected `{} <symbol> <value>`", keyword)
+                    ));
+                }
+
+                let src = symbol(args[0], line)?;
+                let table = match *keyword {
+                    "interpret" => &mut self.interpretations,
+                    _ => &mut self.decompositions
+                };
+
+                table.retain(| (c, _) | *c != src);
+                table.push((src, String::from(args[1])));
+            },
+            "action" => {
+                let src = match args.first() {
+                    Some(word) => symbol(word, line)?,
+                    None => return Err(GrammarError::new(line, "expected `action <symbol> <action>`"))
+                };
+                let action = parse_action(&args[1..], line)?;
+
+                self.actions.retain(| (c, _) | *c != src);
+                self.actions.push((src, action));
+            },
+            other => return Err(GrammarError::new(
+                line,
+                &format!("unknown statement `{}`", other)
+            ))
+        };
+
+        Ok(())
+    }
+
+    /// Check the statements depending on each other, e.g the
+    /// scheduled tables being declared
+    pub fn check(&self) -> Result<(), GrammarError> {
+        let unknown = self.schedule
+            .iter()
+            .find(| name | *name != DEFAULT_TABLE && !self.tables.iter().any(| (n, _) | n == *name));
+
+        match unknown {
+            Some(name) => Err(GrammarError::new(
+                0,
+                &format!("the schedule uses the unknown table `{}`", name)
+            )),
+            None => Ok(())
+        }
+    }
+
+    /// Configured system, not iterated yet
+    pub fn build(&self, renderer: Renderer) -> Lindenmayer {
+        let mut system = Lindenmayer::new(renderer);
+
+        // Variables are declared by the rules
+        let consts: String = self.consts
+            .chars()
+            .filter(| c | !self.rules.iter().any(| (src, _) | src == c))
+            .filter(| c | !self.tables
+                .iter()
+                .any(| (_, rules) | rules.iter().any(| (src, _) | src == c))
+            )
+            .collect();
+
+        system
+            .set_consts(&consts)
+            .set_axiom(&self.axiom);
+
+        if let Some(seed) = self.seed {
+            system.set_seed(seed);
+        }
+
+        if let Some((r, g, b)) = self.background {
+            system.set_background(r, g, b);
+        }
+
+        if let Some(factor) = self.width_decay {
+            system.set_width_decay(factor);
+        }
+
+        if let Some(fields) = self.restored_fields {
+            system.set_restored_fields(fields);
+        }
+
+        for (src, rules) in &self.rules {
+            system.set_rule(*src, rules.clone());
+        }
+
+        for (name, rules) in &self.tables {
+            for (src, rules) in rules {
+                system.set_table_rule(name, *src, rules.clone());
+            }
+        }
+
+        if !self.schedule.is_empty() {
+            let schedule: Vec<&str> = self.schedule
+                .iter()
+                .map(| name | name.as_str())
+                .collect();
+
+            system.set_schedule(&schedule);
+        }
+
+        for (src, action) in &self.actions {
+            system.set_action(*src, action.clone());
+        }
+
+        for (src, value) in &self.interpretations {
+            system.set_interpretation(*src, value);
+        }
+
+        for (src, value) in &self.decompositions {
+            system.set_decomposition(*src, value);
+        }
+
+        for (src, age) in &self.ages {
+            system.set_terminal_age(*src, *age);
+        }
+
+        system
+    }
+
+    /// Configured system, grown until `time` if any, iterated
+    /// `iterations` times otherwise
+    pub fn generate(&self, renderer: Renderer) -> Lindenmayer {
+        let mut system = self.build(renderer);
+
+        match self.time {
+            Some(t) => system.grow(t),
+            None => system.iterate(self.iterations)
+        };
+
+        system
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+# Stochastic plant
+axiom +X
+consts +-[]
+rule X F[+X]F[-X]+X 2
+rule X F[-X]F 1
+rule F FF
+table winter F F
+schedule - winter
+action F forward 10
+action + left 25
+action [ save
+action ] restore
+action X save-turn right 30
+interpret L [+F-F-F]
+iterations 5
+seed 42
+background 0 0 0
+width-decay 0.7
+restore-fields width color
+";
+
+    #[test]
+    fn parse() {
+        let grammar = Grammar::parse(SOURCE).unwrap();
+
+        assert_eq!(grammar.axiom, "+X");
+        assert_eq!(grammar.consts, "+-[]");
+        assert_eq!(grammar.iterations, 5);
+        assert_eq!(grammar.seed, Some(42));
+        assert_eq!(grammar.background, Some((0., 0., 0.)));
+        assert_eq!(grammar.width_decay, Some(0.7));
+        assert_eq!(grammar.schedule, vec!["-", "winter"]);
+        assert_eq!(grammar.interpretations, vec![('L', String::from("[+F-F-F]"))]);
+
+        // Alternatives in declaration order
+        let (src, rules) = &grammar.rules[0];
+        let weights: Vec<usize> = rules.iter().map(| rule | rule.weight()).collect();
+
+        assert_eq!(*src, 'X');
+        assert_eq!(rules[1].value, "F[-X]F");
+        assert_eq!(weights, vec![2, 1]);
+        assert_eq!(grammar.tables[0].0, "winter");
+
+        let fields = grammar.restored_fields.unwrap();
+
+        assert!(fields.width && fields.color && !fields.tropism);
+        assert!(matches!(grammar.actions[0], ('F', Do::Forward(length)) if length == 10.));
+        assert!(matches!(
+            &grammar.actions[4],
+            ('X', Do::SaveAndTurn(angle)) if matches!(angle.side, Side::Right) && angle.value == 30.
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let error = | source: &str | Grammar::parse(source).unwrap_err();
+
+        assert_eq!(error("axiom F\nsprout F"), GrammarError::new(2, "unknown statement `sprout`"));
+        assert_eq!(error("rule FF F"), GrammarError::new(1, "`FF` is not a single symbol"));
+        assert_eq!(error("rule F F x"), GrammarError::new(1, "`x` is not a valid number"));
+        assert_eq!(error("action F jump 1"), GrammarError::new(1, "unknown action `jump`"));
+        assert_eq!(error("action F forward"), GrammarError::new(1, "`forward` expects 1 argument(s)"));
+        assert_eq!(
+            error("schedule spring"),
+            GrammarError::new(0, "the schedule uses the unknown table `spring`")
+        );
+    }
+}
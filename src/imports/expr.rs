@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+
+/// Arithmetic and logic expression of the parametric modules,
+/// both the Python and the C operators are accepted, the angles
+/// of the trigonometric functions are in radians
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Name(String),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(Operator, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(&'static str),
+    Open,
+    Close,
+    Comma
+}
+
+/// Multi-char operators first
+const SYMBOLS: [&str; 16] = [
+    "**", "<=", ">=", "==", "!=", "&&", "||",
+    "+", "-", "*", "/", "%", "^", "<", ">", "!"
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let start = i;
+
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+
+            // Exponent
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let sign = matches!(chars.get(i + 1), Some('+') | Some('-')) as usize;
+
+                if chars.get(i + 1 + sign).is_some_and(| c | c.is_ascii_digit()) {
+                    i += 1 + sign;
+
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+
+            let number: String = chars[start..i].iter().collect();
+
+            tokens.push(Token::Number(
+                number.parse().map_err(| _ | format!("`{}` is not a valid number", number))?
+            ));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            _ => {
+                let rest: String = chars[i..].iter().take(2).collect();
+                let symbol = SYMBOLS
+                    .iter()
+                    .find(| s | rest.starts_with(**s))
+                    .ok_or(format!("unexpected `{}`", c))?;
+
+                tokens.push(Token::Symbol(symbol));
+                i += symbol.len();
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent, from the lowest precedence
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+
+        self.position += 1;
+
+        token
+    }
+
+    /// Consume the next token if it is one of `symbols`
+    fn operator(&mut self, symbols: &[(&str, Operator)]) -> Option<Operator> {
+        let found = symbols.iter().find(| (symbol, _) | match self.peek() {
+            Some(Token::Symbol(s)) => s == symbol,
+            Some(Token::Name(name)) => name == symbol,
+            _ => false
+        });
+
+        if found.is_some() {
+            self.position += 1;
+        }
+
+        found.map(| (_, operator) | *operator)
+    }
+
+    /// Left associative binary operators
+    fn binary(
+        &mut self,
+        symbols: &[(&str, Operator)],
+        operand: fn(&mut Self) -> Result<Expr, String>
+    ) -> Result<Expr, String> {
+        let mut left = operand(self)?;
+
+        while let Some(operator) = self.operator(symbols) {
+            let right = operand(self)?;
+
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        self.binary(&[("or", Operator::Or), ("||", Operator::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        self.binary(&[("and", Operator::And), ("&&", Operator::And)], Self::not)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Name(name)) if name == "not" => {
+                self.position += 1;
+
+                Ok(Expr::Not(Box::new(self.not()?)))
+            },
+            _ => self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        self.binary(&[
+            ("<=", Operator::LessEqual),
+            (">=", Operator::GreaterEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+            ("==", Operator::Equal),
+            ("!=", Operator::NotEqual)
+        ], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        self.binary(&[("+", Operator::Add), ("-", Operator::Sub)], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        self.binary(&[
+            ("*", Operator::Mul),
+            ("/", Operator::Div),
+            ("%", Operator::Rem)
+        ], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Symbol("-")) => {
+                self.position += 1;
+
+                Ok(Expr::Negate(Box::new(self.unary()?)))
+            },
+            Some(Token::Symbol("+")) => {
+                self.position += 1;
+
+                self.unary()
+            },
+            Some(Token::Symbol("!")) => {
+                self.position += 1;
+
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            },
+            _ => self.power()
+        }
+    }
+
+    /// Right associative
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.primary()?;
+
+        match self.operator(&[("**", Operator::Pow), ("^", Operator::Pow)]) {
+            Some(operator) => Ok(Expr::Binary(
+                operator,
+                Box::new(base),
+                Box::new(self.unary()?)
+            )),
+            None => Ok(base)
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Name(name)) => {
+                if self.peek() != Some(&Token::Open) {
+                    return Ok(Expr::Name(name));
+                }
+
+                self.position += 1;
+
+                let mut args = Vec::new();
+
+                if self.peek() == Some(&Token::Close) {
+                    self.position += 1;
+
+                    return Ok(Expr::Call(name, args));
+                }
+
+                loop {
+                    args.push(self.or()?);
+
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::Close) => break,
+                        _ => return Err(format!("missing `)` after the arguments of `{}`", name))
+                    }
+                }
+
+                Ok(Expr::Call(name, args))
+            },
+            Some(Token::Open) => {
+                let expr = self.or()?;
+
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(String::from("missing `)`"))
+                }
+            },
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err(String::from("unexpected end of expression"))
+        }
+    }
+}
+
+fn truth(value: bool) -> f64 {
+    if value { 1. } else { 0. }
+}
+
+impl Expr {
+    /// Read an expression
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0
+        };
+
+        let expr = parser.or()?;
+
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {:?} in `{}`", token, source.trim()))
+        }
+    }
+
+    /// Value of the expression, the booleans being 1 and 0
+    pub fn eval(&self, names: &HashMap<String, f64>) -> Result<f64, String> {
+        let value = match self {
+            Expr::Number(n) => *n,
+            Expr::Name(name) => match name.as_str() {
+                "pi" | "PI" => std::f64::consts::PI,
+                "True" | "true" => 1.,
+                "False" | "false" => 0.,
+                _ => *names
+                    .get(name)
+                    .ok_or(format!("unknown name `{}`", name))?
+            },
+            Expr::Negate(expr) => -expr.eval(names)?,
+            Expr::Not(expr) => truth(expr.eval(names)? == 0.),
+            Expr::Binary(operator, left, right) => {
+                let (a, b) = (left.eval(names)?, right.eval(names)?);
+
+                match operator {
+                    Operator::Add => a + b,
+                    Operator::Sub => a - b,
+                    Operator::Mul => a * b,
+                    Operator::Div => a / b,
+                    Operator::Rem => a % b,
+                    Operator::Pow => a.powf(b),
+                    Operator::Less => truth(a < b),
+                    Operator::LessEqual => truth(a <= b),
+                    Operator::Greater => truth(a > b),
+                    Operator::GreaterEqual => truth(a >= b),
+                    Operator::Equal => truth(a == b),
+                    Operator::NotEqual => truth(a != b),
+                    Operator::And => truth(a != 0. && b != 0.),
+                    Operator::Or => truth(a != 0. || b != 0.)
+                }
+            },
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(| arg | arg.eval(names))
+                    .collect::<Result<Vec<f64>, String>>()?;
+                let arg = | i: usize | args
+                    .get(i)
+                    .copied()
+                    .ok_or(format!("`{}` is missing arguments", name));
+
+                match name.as_str() {
+                    "sin" => arg(0)?.sin(),
+                    "cos" => arg(0)?.cos(),
+                    "tan" => arg(0)?.tan(),
+                    "radians" => arg(0)?.to_radians(),
+                    "degrees" => arg(0)?.to_degrees(),
+                    "sqrt" => arg(0)?.sqrt(),
+                    "abs" => arg(0)?.abs(),
+                    "exp" => arg(0)?.exp(),
+                    "log" => arg(0)?.ln(),
+                    "floor" => arg(0)?.floor(),
+                    "ceil" => arg(0)?.ceil(),
+                    "round" => arg(0)?.round(),
+                    "int" | "trunc" => arg(0)?.trunc(),
+                    "pow" => arg(0)?.powf(arg(1)?),
+                    "min" => args.iter().copied().fold(f64::INFINITY, f64::min),
+                    "max" => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    _ => return Err(format!("unknown function `{}`", name))
+                }
+            }
+        };
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,446 @@
+use std::fs;
+
+use crate::{
+    action::Do,
+    grammar::GrammarError,
+    imports::{Import, Symbols},
+    rule::Rule
+};
+
+/// Segment length of `F`, `D`, `G` and `M`
+const STEP: f64 = 10.;
+
+/// Iterations of a system, the files do not give any
+const ITERATIONS: usize = 4;
+
+/// Default Fractint palette, used by `C0` to `C15`
+const PALETTE: [(f64, f64, f64); 16] = [
+    (0., 0., 0.),
+    (0., 0., 170.),
+    (0., 170., 0.),
+    (0., 170., 170.),
+    (170., 0., 0.),
+    (170., 0., 170.),
+    (170., 85., 0.),
+    (170., 170., 170.),
+    (85., 85., 85.),
+    (85., 85., 255.),
+    (85., 255., 85.),
+    (85., 255., 255.),
+    (255., 85., 85.),
+    (255., 85., 255.),
+    (255., 255., 85.),
+    (255., 255., 255.)
+];
+
+/// System being read
+struct Parser {
+    import: Import,
+    /// Line of the opening brace
+    line: usize,
+    /// Amount of turns for a full circle
+    angle: Option<f64>,
+    symbols: Symbols,
+    /// First error, the rest of the system is skipped
+    error: Option<GrammarError>
+}
+
+/// Read the digits and dots at the beginning of `chars`
+fn number(chars: &[char]) -> String {
+    chars
+        .iter()
+        .take_while(| c | c.is_ascii_digit() || **c == '.')
+        .collect()
+}
+
+impl Parser {
+    fn new(name: &str, line: usize) -> Self {
+        let mut import = Import::new(name);
+
+        import.grammar.iterations = ITERATIONS;
+
+        Self {
+            import,
+            line,
+            angle: None,
+            symbols: Symbols::default(),
+            error: None
+        }
+    }
+
+    /// Action of a command, `None` if it is unsupported
+    fn action(token: &str, angle: f64) -> Option<Do> {
+        let argument = | | token[1..].parse::<f64>().ok();
+
+        let action = match token {
+            "F" | "D" => Do::Forward(STEP),
+            "G" | "M" => Do::Move(STEP),
+            "+" => Do::Left(angle),
+            "-" => Do::Right(angle),
+            "|" => Do::Left(180.),
+            "!" => Do::SwapTurns,
+            "[" => Do::Save,
+            "]" => Do::Restore,
+            _ => match token.chars().next() {
+                Some('\\') => Do::Left(argument()?),
+                Some('/') => Do::Right(argument()?),
+                Some('@') => {
+                    // `I` for the inverse, `Q` for the square root
+                    let count = token[1..]
+                        .chars()
+                        .take_while(| c | *c == 'I' || *c == 'Q')
+                        .count();
+                    let modifiers = &token[1..1 + count];
+                    let mut factor = token[1 + count..].parse::<f64>().ok()?;
+
+                    if modifiers.contains('Q') {
+                        factor = factor.sqrt();
+                    }
+                    if modifiers.contains('I') {
+                        factor = 1. / factor;
+                    }
+
+                    Do::ScaleLength(factor)
+                },
+                Some('C') if token.len() > 1 => {
+                    let (r, g, b) = PALETTE.get(argument()? as usize)?;
+
+                    Do::PenColor(*r, *g, *b)
+                },
+                _ => return None
+            }
+        };
+
+        Some(action)
+    }
+
+    /// Indicate if `token` is a command without translation,
+    /// the other letters being plain variables
+    fn is_unsupported(token: &str) -> bool {
+        let command = token.starts_with(| c: char | !c.is_ascii_alphabetic())
+            || (token.starts_with('C') && token.len() > 1);
+
+        command && Self::action(token, 0.).is_none()
+    }
+
+    /// Turn a Fractint string into single char symbols
+    fn translate(&mut self, value: &str) -> String {
+        let chars: Vec<char> = value
+            .to_uppercase()
+            .chars()
+            .filter(| c | !c.is_whitespace())
+            .collect();
+        let mut translated = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let token: String = match chars[i] {
+                '\\' | '/' | '<' | '>' => {
+                    format!("{}{}", chars[i], number(&chars[i + 1..]))
+                },
+                'C' if chars.get(i + 1).is_some_and(| c | c.is_ascii_digit()) => {
+                    format!("C{}", number(&chars[i + 1..]))
+                },
+                '@' => {
+                    let modifiers: String = chars[i + 1..]
+                        .iter()
+                        .take_while(| c | **c == 'I' || **c == 'Q')
+                        .collect();
+                    let rest = i + 1 + modifiers.len();
+
+                    format!("@{}{}", modifiers, number(&chars[rest..]))
+                },
+                c => c.to_string()
+            };
+
+            i += token.chars().count();
+
+            if Self::is_unsupported(&token) {
+                self.import.add_unsupported(&token);
+            }
+
+            let mut token_chars = token.chars();
+
+            match (token_chars.next(), token_chars.next()) {
+                (Some(c), None) => translated.push(c),
+                _ => translated.push(self.symbols.get(&token))
+            }
+        }
+
+        translated
+    }
+
+    fn statement(&mut self, statement: &str, line: usize) -> Result<(), GrammarError> {
+        let words: Vec<&str> = statement.split_whitespace().collect();
+        let keyword = words.first().map(| w | w.to_lowercase());
+
+        match keyword.as_deref() {
+            None => {},
+            Some("angle") => {
+                let angle: f64 = words
+                    .get(1)
+                    .and_then(| w | w.parse().ok())
+                    .filter(| n: &f64 | *n > 0.)
+                    .ok_or(GrammarError::new(line, "`Angle` expects a positive number"))?;
+
+                self.angle = Some(angle);
+            },
+            Some("axiom") => {
+                let axiom = self.translate(&words[1..].concat());
+
+                self.import.grammar.axiom = axiom;
+            },
+            _ => {
+                let (src, value) = statement
+                    .split_once('=')
+                    .ok_or(GrammarError::new(line, &format!("unknown statement `{}`", statement.trim())))?;
+
+                let src = self.translate(src);
+                let mut chars = src.chars();
+
+                let src = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(GrammarError::new(line, "a rule needs a single symbol"))
+                };
+
+                let value = self.translate(value);
+                let rules = &mut self.import.grammar.rules;
+
+                rules.retain(| (c, _) | *c != src);
+                rules.push((src, vec![Rule::new(&value, 1)]));
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Read a statement unless an earlier one failed
+    fn read(&mut self, statement: &str, line: usize) {
+        if self.error.is_none() {
+            self.error = self.statement(statement, line).err();
+        }
+    }
+
+    /// The system, or its first error prefixed with its name
+    fn finish(self) -> Result<Import, GrammarError> {
+        let name = self.import.name.clone();
+
+        self.bind().map_err(| e | GrammarError::new(e.line, &format!("{}: {}", name, e.message)))
+    }
+
+    /// Bind the actions once the whole system is known
+    fn bind(mut self) -> Result<Import, GrammarError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let angle = 360. / self.angle.ok_or(GrammarError::new(self.line, "missing `Angle`"))?;
+        let grammar = &mut self.import.grammar;
+
+        // Every symbol, in order of appearance
+        let mut symbols: Vec<char> = Vec::new();
+        let values = std::iter::once(&grammar.axiom).chain(
+            grammar.rules
+                .iter()
+                .flat_map(| (_, rules) | rules.iter().map(| rule | &rule.value))
+        );
+
+        for c in values.flat_map(| value | value.chars()) {
+            if !symbols.contains(&c) {
+                symbols.push(c);
+            }
+        }
+
+        for c in symbols {
+            let token = match self.symbols.tokens.iter().find(| (_, s) | *s == c) {
+                Some((token, _)) => token.clone(),
+                None => c.to_string()
+            };
+
+            if let Some(action) = Self::action(&token, angle) {
+                grammar.actions.push((c, action));
+            }
+
+            if !grammar.rules.iter().any(| (src, _) | *src == c) {
+                grammar.consts.push(c);
+            }
+        }
+
+        Ok(self.import)
+    }
+}
+
+/// Read the systems of a Fractint `.l` file
+///
+/// ```text
+/// Koch { ; comments start with a semicolon
+///   Angle 6
+///   Axiom F--F--F
+///   F=F+F--F+F
+/// }
+/// ```
+///
+/// `F` and `D` draw forward, `G` and `M` move forward, `+` and `-`
+/// turn by 360 / `Angle` degrees, `|` turns around, `\nn` and `/nn`
+/// turn by nn degrees, `!` swaps the meaning of `+` and `-` and of
+/// `\` and `/`, `@nn` multiplies the length by nn (`@Inn` by 1 / nn,
+/// `@Qnn` by its square root), `[` and `]` save and restore, `C0` to
+/// `C15` pick a color. The other commands (`<`, `>`) are left without
+/// action and listed in `Import::unsupported`. The systems are
+/// iterated 4 times by default
+///
+/// Each system is returned in order or replaced by its first error,
+/// so a bad system does not prevent reading the others
+pub fn parse(source: &str) -> Vec<Result<Import, GrammarError>> {
+    let mut systems = Vec::new();
+    let mut current: Option<Parser> = None;
+
+    for (i, raw) in source.lines().enumerate() {
+        let line = i + 1;
+        let mut text = raw.split(';').next().unwrap_or_default();
+
+        if current.is_none() {
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            match text.split_once('{') {
+                Some((name, rest)) => {
+                    current = Some(Parser::new(name.trim(), line));
+                    text = rest;
+                },
+                None => {
+                    systems.push(Err(GrammarError::new(line, "expected `<name> {`")));
+                    continue;
+                }
+            }
+        }
+
+        let (statement, end) = match text.split_once('}') {
+            Some((statement, _)) => (statement, true),
+            None => (text, false)
+        };
+
+        if let Some(parser) = current.as_mut() {
+            parser.read(statement, line);
+        }
+
+        if end {
+            if let Some(parser) = current.take() {
+                systems.push(parser.finish());
+            }
+        }
+    }
+
+    if let Some(parser) = current {
+        systems.push(Err(GrammarError::new(
+            parser.line,
+            &format!("`{}` is never closed", parser.import.name)
+        )));
+    }
+
+    systems
+}
+
+/// Read the systems of a Fractint `.l` file at `filename`
+pub fn from_file(filename: &str) -> Result<Vec<Result<Import, GrammarError>>, GrammarError> {
+    let source = fs::read_to_string(filename)
+        .map_err(| e | GrammarError::new(0, &format!("{}: {}", filename, e)))?;
+
+    Ok(parse(&source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Actions in order of appearance, as text
+    fn actions(import: &Import) -> Vec<String> {
+        import.grammar.actions
+            .iter()
+            .map(| (_, action) | format!("{:?}", action))
+            .collect()
+    }
+
+    #[test]
+    fn parse_systems() {
+        let source = "\
+Koch { ; snowflake
+  Angle 6
+  Axiom F--F--F
+  F=F+F--F+F
+}
+
+Commands {
+  angle 4
+  axiom F@IQ4F!+|\\30/45G<10
+}
+";
+        let imports: Vec<Import> = parse(source)
+            .into_iter()
+            .collect::<Result<Vec<Import>, GrammarError>>()
+            .unwrap();
+
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].name, "Koch");
+        assert_eq!(imports[0].grammar.axiom, "F--F--F");
+        assert_eq!(imports[0].grammar.rules[0].1[0].value, "F+F--F+F");
+        assert_eq!(imports[0].grammar.iterations, ITERATIONS);
+        assert_eq!(actions(&imports[0]), vec!["Forward(10.0)", "Right(60.0)", "Left(60.0)"]);
+
+        assert_eq!(actions(&imports[1]), vec![
+            "Forward(10.0)",
+            "ScaleLength(0.5)",
+            "SwapTurns",
+            "Left(90.0)",
+            "Left(180.0)",
+            "Left(30.0)",
+            "Right(45.0)",
+            "Move(10.0)"
+        ]);
+        assert_eq!(imports[1].report(), "`<10` x1");
+    }
+
+    #[test]
+    fn scale_length() {
+        assert!(matches!(Parser::action("@2", 90.), Some(Do::ScaleLength(f)) if f == 2.));
+        assert!(matches!(Parser::action("@I2", 90.), Some(Do::ScaleLength(f)) if f == 0.5));
+        assert!(matches!(Parser::action("@Q9", 90.), Some(Do::ScaleLength(f)) if f == 3.));
+        assert!(Parser::action("@", 90.).is_none());
+    }
+
+    #[test]
+    fn errors() {
+        let source = "\
+NoAngle {
+  Axiom F
+}
+stray text
+BadAngle {
+  Angle -6
+  Axiom F
+}
+Koch {
+  Angle 6
+  Axiom F--F--F
+}
+Open {
+  Angle 6
+";
+        let systems = parse(source);
+        let errors: Vec<GrammarError> = systems
+            .iter()
+            .filter_map(| system | system.clone().err())
+            .collect();
+
+        // The bad systems do not prevent reading the others
+        assert_eq!(systems.len(), 5);
+        assert!(matches!(&systems[3], Ok(import) if import.name == "Koch"));
+        assert_eq!(errors, vec![
+            GrammarError::new(1, "NoAngle: missing `Angle`"),
+            GrammarError::new(4, "expected `<name> {`"),
+            GrammarError::new(6, "BadAngle: `Angle` expects a positive number"),
+            GrammarError::new(13, "`Open` is never closed")
+        ]);
+    }
+}
@@ -0,0 +1,723 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::Path
+};
+
+use crate::{
+    action::Do,
+    grammar::GrammarError,
+    imports::{
+        expr::Expr,
+        Import,
+        Symbols
+    },
+    rule::Rule
+};
+
+/// Turtle units per model unit
+const UNIT: f64 = 10.;
+
+/// Turn angle of `+` and `-` without argument
+const DEFAULT_ANGLE: f64 = 60.;
+
+/// Largest amount of distinct concrete modules
+const MAX_MODULES: usize = 6000;
+
+/// Multi-char modules known without a `module` declaration
+const PREDEFINED: [&str; 10] = ["@O", "@o", "@M", "@m", "@Tp", "@Ts", "@R", "@v", "@Gc", "@Ge"];
+
+/// Module of a successor, its arguments being expressions
+#[derive(Debug, Clone)]
+struct Template {
+    name: String,
+    args: Vec<Expr>
+}
+
+/// `name(params) : condition --> successor`
+#[derive(Debug, Clone)]
+struct Production {
+    name: String,
+    params: Vec<String>,
+    condition: Option<Expr>,
+    successor: Vec<Template>
+}
+
+/// Module with its evaluated arguments
+#[derive(Debug, Clone, PartialEq)]
+struct Module {
+    name: String,
+    args: Vec<f64>
+}
+
+impl Module {
+    /// Text of the module, e.g `F(1.5)`
+    fn token(&self) -> String {
+        if self.args.is_empty() {
+            return self.name.clone();
+        }
+
+        let args: Vec<String> = self.args.iter().map(| arg | arg.to_string()).collect();
+
+        format!("{}({})", self.name, args.join(","))
+    }
+
+    fn arg(&self, i: usize) -> Option<f64> {
+        self.args.get(i).copied()
+    }
+
+    /// Turtle action, `None` for the plain variables
+    fn action(&self) -> Result<Option<Do>, ()> {
+        let action = match self.name.as_str() {
+            "F" => Do::Forward(UNIT * self.arg(0).unwrap_or(1.)),
+            "f" => Do::Move(UNIT * self.arg(0).unwrap_or(1.)),
+            "+" => Do::Left(self.arg(0).unwrap_or(DEFAULT_ANGLE)),
+            "-" => Do::Right(self.arg(0).unwrap_or(DEFAULT_ANGLE)),
+            "|" => Do::Left(180.),
+            "[" => Do::Save,
+            "]" => Do::Restore,
+            "_" | "!" => match self.arg(0) {
+                Some(width) => Do::LineSize(UNIT * width),
+                None => return Err(())
+            },
+            // Applied while rewriting
+            "%" => return Ok(None),
+            name if name.starts_with(char::is_alphabetic) => return Ok(None),
+            _ => return Err(())
+        };
+
+        Ok(Some(action))
+    }
+}
+
+/// Section of the file being read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    Production,
+    Decomposition,
+    Homomorphism,
+    /// Unsupported section, its lines are skipped
+    Ignored,
+    End
+}
+
+/// Split `text` into module names and raw arguments
+fn modules(text: &str, names: &[String]) -> Result<Vec<(String, Vec<String>)>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut modules = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // Longest declared name, a single char otherwise
+        let rest: String = chars[i..].iter().collect();
+        let name = names
+            .iter()
+            .filter(| name | rest.starts_with(name.as_str()))
+            .max_by_key(| name | name.len())
+            .cloned()
+            .unwrap_or_else(|| chars[i].to_string());
+
+        i += name.chars().count();
+
+        let mut args = Vec::new();
+
+        if chars.get(i) == Some(&'(') {
+            let mut depth = 0;
+            let mut current = String::new();
+
+            loop {
+                let c = *chars
+                    .get(i)
+                    .ok_or(format!("missing `)` after `{}`", name))?;
+
+                i += 1;
+
+                match c {
+                    '(' if depth == 0 => depth += 1,
+                    '(' => {
+                        depth += 1;
+                        current.push(c);
+                    },
+                    ')' if depth == 1 => {
+                        args.push(current.trim().to_string());
+                        break;
+                    },
+                    ')' => {
+                        depth -= 1;
+                        current.push(c);
+                    },
+                    ',' if depth == 1 => {
+                        args.push(current.trim().to_string());
+                        current.clear();
+                    },
+                    c => current.push(c)
+                }
+            }
+        }
+
+        modules.push((name, args));
+    }
+
+    Ok(modules)
+}
+
+/// Modules with expressions as arguments
+fn templates(text: &str, names: &[String]) -> Result<Vec<Template>, String> {
+    modules(text, names)?
+        .into_iter()
+        .map(| (name, args) | Ok(Template {
+            name,
+            args: args
+                .iter()
+                .map(| arg | Expr::parse(arg))
+                .collect::<Result<Vec<Expr>, String>>()?
+        }))
+        .collect()
+}
+
+/// Modules with their evaluated arguments
+fn evaluate(templates: &[Template], names: &HashMap<String, f64>) -> Result<Vec<Module>, String> {
+    templates
+        .iter()
+        .map(| template | Ok(Module {
+            name: template.name.clone(),
+            args: template.args
+                .iter()
+                .map(| arg | arg.eval(names))
+                .collect::<Result<Vec<f64>, String>>()?
+        }))
+        .collect()
+}
+
+/// Evaluate the successor of a production matching `module`,
+/// `None` if there is none
+fn derive(
+    productions: &[Production],
+    module: &Module,
+    constants: &HashMap<String, f64>
+) -> Result<Option<Vec<Module>>, String> {
+    for production in productions {
+        if production.name != module.name || production.params.len() != module.args.len() {
+            continue;
+        }
+
+        let mut names = constants.clone();
+
+        for (param, value) in production.params.iter().zip(&module.args) {
+            names.insert(param.clone(), *value);
+        }
+
+        if let Some(condition) = &production.condition {
+            if condition.eval(&names)? == 0. {
+                continue;
+            }
+        }
+
+        return Ok(Some(evaluate(&production.successor, &names)?));
+    }
+
+    Ok(None)
+}
+
+/// Concrete modules found while deriving
+#[derive(Default)]
+struct Instances {
+    modules: Vec<Module>,
+    /// Index of each module by its token
+    indexes: HashMap<String, usize>,
+    /// Smallest derivation depth of each module
+    depths: Vec<usize>,
+    /// Modules to derive, with their derivation depth
+    queue: VecDeque<(usize, usize)>
+}
+
+impl Instances {
+    /// Index of `module`, queued at `depth` when first seen or
+    /// when reached earlier in the derivation than before
+    fn index(&mut self, module: Module, depth: usize) -> usize {
+        let token = module.token();
+
+        if let Some(&i) = self.indexes.get(&token) {
+            if depth < self.depths[i] {
+                self.depths[i] = depth;
+                self.queue.push_back((i, depth));
+            }
+
+            return i;
+        }
+
+        let i = self.modules.len();
+
+        self.modules.push(module);
+        self.indexes.insert(token, i);
+        self.depths.push(depth);
+        self.queue.push_back((i, depth));
+
+        i
+    }
+}
+
+/// File being read
+struct Parser {
+    import: Import,
+    section: Section,
+    axiom: Vec<Template>,
+    iterations: usize,
+    productions: Vec<Production>,
+    decompositions: Vec<Production>,
+    homomorphisms: Vec<Production>,
+    /// Python globals and C defines
+    constants: HashMap<String, f64>,
+    /// Multi-char module names
+    names: Vec<String>,
+    symbols: Symbols
+}
+
+impl Parser {
+    fn new(name: &str) -> Self {
+        Self {
+            import: Import::new(name),
+            section: Section::Header,
+            axiom: Vec::new(),
+            iterations: 1,
+            productions: Vec::new(),
+            decompositions: Vec::new(),
+            homomorphisms: Vec::new(),
+            constants: HashMap::new(),
+            names: PREDEFINED.iter().map(| name | String::from(*name)).collect(),
+            symbols: Symbols::default()
+        }
+    }
+
+    /// Read `name(params) : condition --> successor`
+    fn production(&mut self, text: &str) -> Result<(), String> {
+        let (head, successor) = match text.split_once("-->").or_else(|| text.split_once("->")) {
+            Some(split) => split,
+            None => {
+                self.import.add_unsupported("production without `-->`");
+                return Ok(());
+            }
+        };
+
+        let (predecessor, condition) = match head.split_once(':') {
+            Some((predecessor, condition)) => (predecessor, Some(Expr::parse(condition)?)),
+            None => (head, None)
+        };
+
+        let mut predecessor = modules(predecessor, &self.names)?;
+
+        if predecessor.len() != 1 {
+            let context = predecessor
+                .iter()
+                .any(| (name, _) | name == "<" || name == ">");
+
+            self.import.add_unsupported(if context {
+                "context-sensitive production"
+            } else {
+                "production with several predecessors"
+            });
+            return Ok(());
+        }
+
+        let (name, params) = predecessor.remove(0);
+        let production = Production {
+            name,
+            params,
+            condition,
+            successor: templates(successor, &self.names)?
+        };
+
+        match self.section {
+            Section::Decomposition => self.decompositions.push(production),
+            Section::Homomorphism => self.homomorphisms.push(production),
+            _ => self.productions.push(production)
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self, text: &str) -> Result<(), String> {
+        let text = text.trim();
+
+        if text.is_empty() || self.section == Section::End {
+            return Ok(());
+        }
+
+        let lower = text.to_lowercase();
+        let (keyword, rest) = match text.split_once(':') {
+            Some((keyword, rest)) => (keyword.trim().to_lowercase(), rest.trim()),
+            None => (lower.clone(), "")
+        };
+
+        match keyword.as_str() {
+            "axiom" => self.axiom = templates(rest, &self.names)?,
+            "derivation length" => {
+                self.iterations = Expr::parse(rest)?.eval(&self.constants)? as usize;
+            },
+            "production" | "productions" => self.section = Section::Production,
+            "homomorphism" | "interpretation" => self.section = Section::Homomorphism,
+            "endlsystem" => self.section = Section::End,
+            "decomposition" => self.section = Section::Decomposition,
+            "group" => {
+                self.import.add_unsupported(&keyword);
+                self.section = Section::Ignored;
+            },
+            "endgroup" => self.section = Section::Production,
+            // Identifier of a cpfg system
+            "lsystem" => {},
+            "seed" => self.import.grammar.seed = Some(Expr::parse(rest)?.eval(&self.constants)? as u64),
+            "ignore" | "consider" | "maximum depth" | "decomposition maximum depth" => {
+                self.import.add_unsupported(&keyword);
+            },
+            _ if lower.starts_with("module ") => {
+                for declaration in text[7..].split(',') {
+                    let name = declaration
+                        .split('(')
+                        .next()
+                        .unwrap_or_default()
+                        .trim();
+
+                    if !name.is_empty() {
+                        self.names.push(String::from(name));
+                    }
+                }
+            },
+            _ if lower.starts_with("#define ") => {
+                let words: Vec<&str> = text.split_whitespace().collect();
+
+                match (words.get(1), words.get(2..)) {
+                    (Some(name), Some(value)) if !value.is_empty() => {
+                        let value = Expr::parse(&value.join(" "))?.eval(&self.constants)?;
+
+                        self.constants.insert(String::from(*name), value);
+                    },
+                    _ => return Err(String::from("expected `#define <name> <value>`"))
+                }
+            },
+            _ => match self.section {
+                Section::Production
+                    | Section::Decomposition
+                    | Section::Homomorphism => self.production(text)?,
+                Section::Ignored | Section::End => {},
+                // cpfg productions follow the axiom without a section
+                Section::Header if text.contains("-->") => self.production(text)?,
+                Section::Header => {
+                    // Python globals, e.g `angle = 30`
+                    let assignment = text
+                        .split_once('=')
+                        .filter(| (name, value) | !value.starts_with('=')
+                            && !name.trim().is_empty()
+                            && name.trim().chars().all(| c | c.is_alphanumeric() || c == '_'));
+
+                    match assignment.map(| (name, value) | (name, Expr::parse(value))) {
+                        Some((name, Ok(value))) => {
+                            let value = value.eval(&self.constants)?;
+
+                            self.constants.insert(String::from(name.trim()), value);
+                        },
+                        _ => self.import.add_unsupported("python code")
+                    }
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Single char standing for `module`
+    fn symbol(&mut self, module: &Module) -> char {
+        let token = module.token();
+        let mut chars = token.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => self.symbols.get(&token)
+        }
+    }
+
+    /// Symbol of `module` with its action, once per symbol
+    fn bind(&mut self, module: &Module, bound: &mut Vec<char>) -> char {
+        let c = self.symbol(module);
+
+        if bound.contains(&c) {
+            return c;
+        }
+
+        bound.push(c);
+
+        match module.action() {
+            Ok(Some(action)) => self.import.grammar.actions.push((c, action)),
+            Ok(None) => {},
+            Err(_) => self.import.add_unsupported(&module.name)
+        }
+
+        c
+    }
+
+    /// Instantiate the parametric modules reachable from the axiom
+    /// within the derivation length, each of them becoming a symbol
+    fn finish(mut self) -> Result<Import, String> {
+        let mut instances = Instances::default();
+
+        let axiom_symbols: Vec<usize> = evaluate(&self.axiom, &self.constants)?
+            .into_iter()
+            .map(| module | instances.index(module, 0))
+            .collect();
+
+        let mut rules: Vec<(usize, Vec<usize>)> = Vec::new();
+        let mut decompositions: Vec<(usize, Vec<usize>)> = Vec::new();
+        // Modules already given a rule or a decomposition
+        let mut derived: HashSet<usize> = HashSet::new();
+        let mut decomposed: HashSet<usize> = HashSet::new();
+
+        while let Some((i, depth)) = instances.queue.pop_front() {
+            if instances.modules.len() > MAX_MODULES {
+                return Err(format!("more than {} distinct modules", MAX_MODULES));
+            }
+
+            // Queued again since, from an earlier depth
+            if depth > instances.depths[i] {
+                continue;
+            }
+
+            let module = &instances.modules[i];
+
+            // Same generation as the decomposed module
+            if let Some(parts) = derive(&self.decompositions, module, &self.constants)? {
+                let parts = parts
+                    .into_iter()
+                    .map(| module | instances.index(module, depth))
+                    .collect();
+
+                if decomposed.insert(i) {
+                    decompositions.push((i, parts));
+                }
+            }
+
+            if depth >= self.iterations {
+                continue;
+            }
+
+            let module = &instances.modules[i];
+
+            if let Some(successor) = derive(&self.productions, module, &self.constants)? {
+                let successor = successor
+                    .into_iter()
+                    .map(| module | instances.index(module, depth + 1))
+                    .collect();
+
+                if derived.insert(i) {
+                    rules.push((i, successor));
+                }
+            }
+        }
+
+        let modules = instances.modules;
+        let symbols: Vec<char> = modules
+            .iter()
+            .map(| module | self.symbol(module))
+            .collect();
+        let text = | indexes: &[usize] | indexes.iter().map(| i | symbols[*i]).collect::<String>();
+
+        let grammar = &mut self.import.grammar;
+
+        grammar.axiom = text(&axiom_symbols);
+        grammar.iterations = self.iterations;
+
+        for (i, successor) in &rules {
+            grammar.rules.push((symbols[*i], vec![Rule::new(&text(successor), 1)]));
+        }
+
+        for (i, parts) in &decompositions {
+            grammar.decompositions.push((symbols[*i], text(parts)));
+        }
+
+        let mut bound = Vec::new();
+
+        for (i, module) in modules.iter().enumerate() {
+            match derive(&self.homomorphisms, module, &self.constants)? {
+                // Drawn through its homomorphism image
+                Some(image) => {
+                    let value: String = image
+                        .iter()
+                        .map(| module | self.bind(module, &mut bound))
+                        .collect();
+
+                    self.import.grammar.interpretations.push((symbols[i], value));
+                },
+                None => {
+                    self.bind(module, &mut bound);
+                }
+            }
+
+            if !rules.iter().any(| (r, _) | *r == i) {
+                self.import.grammar.consts.push(symbols[i]);
+            }
+        }
+
+        Ok(self.import)
+    }
+}
+
+/// Remove the comments of a line, `in_block` tells if a C comment
+/// is still open
+fn strip_comments(line: &str, in_block: &mut bool) -> String {
+    let mut text = String::new();
+    let mut rest = line;
+
+    loop {
+        if *in_block {
+            match rest.find("*/") {
+                Some(end) => {
+                    rest = &rest[end + 2..];
+                    *in_block = false;
+                },
+                None => return text
+            }
+        }
+
+        match rest.find("/*") {
+            Some(start) => {
+                text.push_str(&rest[..start]);
+                rest = &rest[start + 2..];
+                *in_block = true;
+            },
+            None => {
+                text.push_str(rest);
+                break;
+            }
+        }
+    }
+
+    if text.trim_start().starts_with("#define") {
+        return text;
+    }
+
+    let end = [text.find('#'), text.find("//")]
+        .iter()
+        .flatten()
+        .min()
+        .copied();
+
+    match end {
+        Some(end) => text[..end].to_string(),
+        None => text
+    }
+}
+
+/// Read a L-Py or cpfg L-system
+///
+/// ```text
+/// module Apex
+/// angle = 30
+/// Axiom: Apex(1)
+/// derivation length: 6
+/// production:
+/// Apex(x) : x < 32 --> F(x)[+(angle)Apex(x * 2)][-(angle)Apex(x * 2)]
+/// homomorphism:
+/// Apex(x) --> [+F(0.5)][-F(0.5)]
+/// endlsystem
+/// ```
+///
+/// The parametric modules are instantiated with their arguments into
+/// single symbols, each one having its evaluated production, so the
+/// system can only be derived up to its `derivation length`. The
+/// decomposition and homomorphism productions become decomposition
+/// and interpretation rules. `F`, `f`, `+`, `-`, `|`, `[`, `]` and
+/// `_` (or `!`) are translated, with a length unit of 10. Python code,
+/// context-sensitive productions, 3D and the other turtle commands are
+/// listed in `Import::unsupported`
+pub fn parse(source: &str) -> Result<Import, GrammarError> {
+    let mut parser = Parser::new("lsystem");
+    let mut in_block = false;
+
+    for (i, raw) in source.lines().enumerate() {
+        let text = strip_comments(raw, &mut in_block);
+
+        parser
+            .statement(&text)
+            .map_err(| e | GrammarError::new(i + 1, &e))?;
+    }
+
+    parser
+        .finish()
+        .map_err(| e | GrammarError::new(0, &e))
+}
+
+/// Read a L-Py or cpfg L-system at `filename`, named after the file
+pub fn from_file(filename: &str) -> Result<Import, GrammarError> {
+    let source = fs::read_to_string(filename)
+        .map_err(| e | GrammarError::new(0, &format!("{}: {}", filename, e)))?;
+
+    let mut import = parse(&source)?;
+
+    if let Some(stem) = Path::new(filename).file_stem().and_then(| s | s.to_str()) {
+        import.name = String::from(stem);
+    }
+
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Actions in order of appearance, as text
+    fn actions(import: &Import) -> Vec<String> {
+        import.grammar.actions
+            .iter()
+            .map(| (_, action) | format!("{:?}", action))
+            .collect()
+    }
+
+    #[test]
+    fn instantiate() {
+        let source = "\
+module Apex
+angle = 30
+Axiom: Apex(1)
+derivation length: 2
+production:
+Apex(x) : x < 32 --> F(x)[+(angle)Apex(x * 2)]
+endlsystem
+";
+        let import = parse(source).unwrap();
+        let grammar = &import.grammar;
+
+        // Apex(1) and Apex(2) are derived, Apex(4) is too deep
+        assert_eq!(grammar.iterations, 2);
+        assert_eq!(grammar.axiom.chars().count(), 1);
+        assert_eq!(grammar.rules.len(), 2);
+        assert_eq!(grammar.rules[0].1[0].value.chars().count(), 5);
+        assert_eq!(actions(&import), vec![
+            "Forward(10.0)",
+            "Save",
+            "Left(30.0)",
+            "Restore",
+            "Forward(20.0)"
+        ]);
+        assert!(import.unsupported.is_empty());
+    }
+
+    #[test]
+    fn unsupported() {
+        let import = parse("Axiom: {F(1).F(1).F(1).}\nderivation length: 1").unwrap();
+
+        assert_eq!(actions(&import), vec!["Forward(10.0)"]);
+        assert_eq!(import.report(), "`{` x1, `.` x1, `}` x1");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("Axiom: F(1").unwrap_err(),
+            GrammarError::new(1, "missing `)` after `F`")
+        );
+    }
+}
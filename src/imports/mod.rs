@@ -0,0 +1,82 @@
+use crate::grammar::Grammar;
+
+/// Fractint `.l` files
+pub mod fractint;
+/// L-Py and cpfg L-systems
+pub mod lpy;
+/// Parametric module expressions
+pub mod expr;
+
+/// First char handed out for the commands having arguments,
+/// the start of the Unicode private use area
+const FIRST_SYMBOL: u32 = 0xE000;
+
+/// Command that could not be translated
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsupported {
+    pub command: String,
+    /// Occurrences in the axiom and the rules
+    pub count: usize
+}
+
+/// System read from another format
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub name: String,
+    pub grammar: Grammar,
+    /// Commands left without action, in order of appearance
+    pub unsupported: Vec<Unsupported>
+}
+
+impl Import {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            grammar: Grammar::new(),
+            unsupported: Vec::new()
+        }
+    }
+
+    /// Count an occurrence of a command without action
+    pub fn add_unsupported(&mut self, command: &str) {
+        match self.unsupported.iter_mut().find(| u | u.command == command) {
+            Some(unsupported) => unsupported.count += 1,
+            None => self.unsupported.push(Unsupported {
+                command: String::from(command),
+                count: 1
+            })
+        }
+    }
+
+    /// Readable list of the commands without action, empty if none
+    pub fn report(&self) -> String {
+        self.unsupported
+            .iter()
+            .map(| u | format!("`{}` x{}", u.command, u.count))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// Single chars standing for the multi-char commands
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Symbols {
+    /// Commands with their char, in order of appearance
+    pub tokens: Vec<(String, char)>
+}
+
+impl Symbols {
+    /// Char of `token`, a new one if it has never been seen
+    pub fn get(&mut self, token: &str) -> char {
+        if let Some((_, c)) = self.tokens.iter().find(| (t, _) | t == token) {
+            return *c;
+        }
+
+        let c = char::from_u32(FIRST_SYMBOL + self.tokens.len() as u32)
+            .expect("Too many commands");
+
+        self.tokens.push((String::from(token), c));
+
+        c
+    }
+}
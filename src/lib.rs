@@ -12,5 +12,17 @@ pub mod state;
 pub mod action;
 /// Square
 pub mod square;
+/// Drawn area tracking
+pub mod bounds;
 /// Rule abstraction
 pub mod rule;
+/// Backend-neutral recorded geometry
+pub mod drawing;
+/// Output formats built from a `Drawing`
+pub mod exports;
+/// Ready-made classic L-systems
+pub mod presets;
+/// Textual grammar files
+pub mod grammar;
+/// Systems read from other L-system formats
+pub mod imports;
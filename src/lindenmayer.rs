@@ -1,31 +1,44 @@
-use std::collections::HashMap;
-use weighted_rand::builder::*;
+use std::{
+    collections::HashMap,
+    io
+};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    SeedableRng
+};
 
 use crate::{
     models::{
         rules_model::Rules,
         action_model::Action,
-        render_model::Render
+        render_model::Render,
+        export_model::{Export, ExportPages},
+        environment_model::{Environment, Query}
     },
     action::Do,
-    state::ScreenPosition,
-    renders::renderer::Renderer, rule::{Rule, RulesWrap}
+    drawing::Drawing,
+    state::{ScreenPosition, SizeType, StateFields, Tropism},
+    renders::{renderer::Renderer, recorder::Recorder},
+    rule::{Rule, RulesWrap}
 };
 
-#[derive(Debug, Clone)]
+/// Scheduled table name standing for the rules set with `set_rule`
+pub const DEFAULT_TABLE: &str = "-";
+
+/// Symbol removing the rest of its branch when produced
+pub const CUT: char = '%';
+
+/// Default largest amount of decomposition passes per step
+const DECOMPOSITION_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Default)]
 pub struct LState {
     /// Current line value
     pub value: String,
 }
 
-impl Default for LState {
-    fn default() -> Self {
-        Self {
-            value: String::from(""),
-         }
-    }
-}
-
+#[derive(Default)]
 pub struct LData {
     /// Containing variables
     pub vars: Vec<char>,
@@ -33,15 +46,6 @@ pub struct LData {
     pub consts: Vec<char>,
 }
 
-impl Default for LData {
-    fn default() -> Self {
-        Self {
-            vars: Vec::new(),
-            consts: Vec::new(),
-        }
-    }
-}
-
 impl LData {
     pub fn new() -> Self {
         Self::default()
@@ -53,12 +57,46 @@ pub struct Lindenmayer {
     data: LData,
     /// Current state
     current_state: LState,
+    /// Beginning value, developed by `grow`
+    axiom: String,
+    /// Age at which a symbol is replaced while growing
+    terminal_ages: HashMap<char, f64>,
+    /// Length scale of each symbol of the state, set by `grow`
+    growth: Option<Vec<f64>>,
     /// Rules table
     rules: HashMap<char, Vec<Rule>>,
+    /// Named rules tables, replacing `rules` when scheduled
+    tables: HashMap<String, HashMap<char, Vec<Rule>>>,
+    /// Name of the table used at each step, from its number
+    schedule: Option<Box<dyn Fn(usize) -> String>>,
+    /// Steps applied since the axiom
+    generation: usize,
     /// Actions for vars / consts
     actions: HashMap<char, Do>,
+    /// Interpretation rules, only expanded while drawing
+    interpretations: HashMap<char, String>,
+    /// Decomposition rules, applied after each step
+    decompositions: HashMap<char, String>,
+    /// Largest amount of decomposition passes per step
+    decomposition_depth: usize,
+    /// Symbols reporting their turtle state
+    query_symbols: Vec<char>,
+    /// Symbols sent to the environment after each step
+    communication_symbols: Vec<char>,
+    /// Answers the communication symbols
+    environment: Option<Box<dyn Environment>>,
     /// Graphics cursor
     cursor: Box<dyn Render>,
+    /// Background color, also applied to recorded drawings
+    background: Option<(f64, f64, f64)>,
+    /// Pen size factor on each saved state, also applied to recorded drawings
+    width_decay: f64,
+    /// Parts of the turtle state restored, also applied to recorded drawings
+    restored_fields: StateFields,
+    /// Picks the stochastic rules
+    rng: StdRng,
+    /// Seed shared with the renderers, random if `None`
+    seed: Option<u64>,
 }
 
 impl Lindenmayer {
@@ -66,9 +104,26 @@ impl Lindenmayer {
         Self {
             data: LData::new(),
             current_state: LState::default(),
+            axiom: String::new(),
+            terminal_ages: HashMap::new(),
+            growth: None,
             rules: HashMap::new(),
+            tables: HashMap::new(),
+            schedule: None,
+            generation: 0,
             actions: HashMap::new(),
-            cursor: render.get_render_obj()
+            interpretations: HashMap::new(),
+            decompositions: HashMap::new(),
+            decomposition_depth: DECOMPOSITION_DEPTH,
+            query_symbols: Vec::new(),
+            communication_symbols: Vec::new(),
+            environment: None,
+            cursor: render.get_render_obj(),
+            background: None,
+            width_decay: 1.,
+            restored_fields: StateFields::default(),
+            rng: StdRng::from_entropy(),
+            seed: None
         }
     }
 
@@ -114,6 +169,9 @@ impl Lindenmayer {
     /// Set the beginning value for the system
     pub fn set_axiom(&mut self, value: &str) -> &mut Self {
         self.current_state.value = String::from(value);
+        self.axiom = String::from(value);
+        self.growth = None;
+        self.generation = 0;
         
         self
     }
@@ -122,60 +180,350 @@ impl Lindenmayer {
         // Overwrite state value with the rules table
         let mut next_value = String::from("");
 
+        // Scheduled table, the default rules otherwise
+        let rules = match &self.schedule {
+            Some(schedule) => {
+                let name = schedule(self.generation);
+
+                match name.as_str() {
+                    DEFAULT_TABLE => &self.rules,
+                    _ => self.tables
+                        .get(&name)
+                        .unwrap_or_else(| | panic!("Unknown rules table {}", name))
+                }
+            },
+            None => &self.rules
+        };
+
         // Building new string
         for c in self.current_state.value.chars() {
-            let part = match self.get_rule(c) {
-                Some(value) => {
-                    let weights: Vec<u32> = value
-                        .iter()
-                        .map(| rule | rule.weight() as u32)
-                        .collect();
-                    
-                    let values: Vec<&str> = value
-                        .iter()
-                        .map(| rule | &*rule.value)
-                        .collect();
-
-                    let builder = WalkerTableBuilder::new(&weights);
-                    let wa_table = builder.build();
-                    
-                    values[wa_table.next()].to_string()
-                },
-                None => c.to_string()
+            match rules.get(&c) {
+                Some(value) => next_value.push_str(&choose(value, &mut self.rng).value),
+                None => next_value.push(c)
             };
-
-            next_value.push_str(&part);
         }
 
         // New current state
         self.current_state = LState {
-            value: next_value,
+            value: prune(&next_value),
         };
+        self.growth = None;
+        self.generation += 1;
+    }
+
+    /// Run the actions linked to the chars of `value` on `cursor`,
+    /// the symbols having an interpretation rule are replaced by it.
+    /// `growth` scales the lengths of each char actions
+    fn interpret(
+        value: &str,
+        growth: Option<&[f64]>,
+        actions: &HashMap<char, Do>,
+        interpretations: &HashMap<char, String>,
+        cursor: &mut dyn Render
+    ) {
+        let mut expanding = Vec::new();
+
+        // Iterate over the chars
+        for (i, c) in value.chars().enumerate() {
+            let scale = growth
+                .and_then(| growth | growth.get(i))
+                .copied()
+                .unwrap_or(1.);
+
+            Self::interpret_symbol(c, scale, actions, interpretations, cursor, &mut expanding);
+        }
+    }
+
+    /// `expanding` holds the symbols being replaced, they run their
+    /// own action when they appear inside their interpretation
+    fn interpret_symbol(
+        c: char,
+        scale: f64,
+        actions: &HashMap<char, Do>,
+        interpretations: &HashMap<char, String>,
+        cursor: &mut dyn Render,
+        expanding: &mut Vec<char>
+    ) {
+        if let Some(image) = interpretations.get(&c) {
+            if !expanding.contains(&c) {
+                expanding.push(c);
+
+                for symbol in image.chars() {
+                    Self::interpret_symbol(symbol, scale, actions, interpretations, cursor, expanding);
+                }
+
+                expanding.pop();
+
+                return;
+            }
+        }
+
+        // Get linked action and check if its linked
+        if let Some(action) = actions.get(&c) {
+            execute(cursor, action.scaled(scale));
+        }
     }
 
     /// Draw / compose the graphic figure
     pub fn draw(&mut self) -> &mut Self {
-        let state = self.current_state.clone();
-        let chars = state.value.chars();
+        Self::interpret(
+            &self.current_state.value,
+            self.growth.as_deref(),
+            &self.actions,
+            &self.interpretations,
+            self.cursor.as_mut()
+        );
 
-        // Iterate over the chars
-        for c in chars {
-            // Get linked action and check if its linked
-            let action = self.get_action(c);
-
-            // Execute action
-            match action {
-                Some(value) => self.call(value),
-                None => continue
+        self
+    }
+
+    /// Recorder with the system seed and background
+    fn recorder(&self) -> Recorder {
+        let mut recorder = Recorder::new(SizeType::Auto);
+
+        if let Some(seed) = self.seed {
+            recorder.set_seed(seed);
+        }
+
+        if let Some((r, g, b)) = self.background {
+            recorder.set_bg(r, g, b);
+        }
+
+        recorder.set_width_decay(self.width_decay);
+        recorder.set_restored_fields(self.restored_fields);
+
+        recorder
+    }
+
+    /// Interpret the current state into a backend-neutral drawing
+    pub fn record(&self) -> Drawing {
+        let mut recorder = self.recorder();
+
+        Self::interpret(
+            &self.current_state.value,
+            self.growth.as_deref(),
+            &self.actions,
+            &self.interpretations,
+            &mut recorder
+        );
+
+        recorder.drawing()
+    }
+
+    /// Record the current state and write it with `exporter`
+    pub fn export<E: Export>(&mut self, exporter: &E, filename: &str) -> io::Result<()> {
+        exporter.save(&self.record(), filename)
+    }
+
+    /// Replace the symbols having a decomposition rule until none
+    /// is left or the maximum depth is reached
+    fn decompose(&mut self) {
+        let value = std::mem::take(&mut self.current_state.value);
+
+        self.current_state.value = prune(&self.decomposed(value));
+    }
+
+    /// `value` with its decomposable symbols replaced
+    fn decomposed(&self, mut value: String) -> String {
+        for _ in 0..self.decomposition_depth {
+            if !value.chars().any(| c | self.decompositions.contains_key(&c)) {
+                break;
+            }
+
+            value = value
+                .chars()
+                .map(| c | match self.decompositions.get(&c) {
+                    Some(value) => value.clone(),
+                    None => c.to_string()
+                })
+                .collect();
+        }
+
+        value
+    }
+
+    /// Time at which a module of `c` born at `birth` is replaced,
+    /// `None` if it is never replaced
+    fn death(&self, c: char, birth: f64) -> Option<f64> {
+        match (self.rules.contains_key(&c), self.terminal_ages.get(&c)) {
+            (true, Some(age)) => Some(birth + age),
+            _ => None
+        }
+    }
+
+    /// Develop the axiom up to the time `t` instead of steps: every
+    /// symbol having a terminal age is replaced by its rule once it
+    /// reaches this age, its successors starting at age 0, the other
+    /// symbols are never replaced and the schedule is not used. The lengths
+    /// of the actions grow with the age of their symbol until the
+    /// terminal age, so a growth animation is smooth. A seeded system
+    /// gives the same development for every `t`
+    pub fn grow(&mut self, t: f64) -> &mut Self {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy()
+        };
+
+        // Symbols with their birth time
+        let mut modules: Vec<(char, f64)> = self.axiom
+            .chars()
+            .map(| c | (c, 0.))
+            .collect();
+
+        // Chronological replacements, the random choices are the same
+        // before `t` whatever `t` is
+        loop {
+            let next = modules
+                .iter()
+                .filter_map(| (c, birth) | self.death(*c, *birth))
+                .fold(f64::INFINITY, f64::min);
+
+            if next > t {
+                break;
+            }
+
+            let mut grown = Vec::with_capacity(modules.len());
+
+            for (c, birth) in modules {
+                match self.death(c, birth) {
+                    Some(death) if death <= next => {
+                        let successor = choose(&self.rules[&c], &mut rng).value.clone();
+
+                        grown.extend(self.decomposed(successor).chars().map(| s | (s, death)));
+                    },
+                    _ => grown.push((c, birth))
+                }
             }
+
+            let mut pruner = Pruner::default();
+
+            grown.retain(| (c, _) | pruner.keep(*c));
+            modules = grown;
+        }
+
+        let growth = modules
+            .iter()
+            .map(| (c, birth) | match self.terminal_ages.get(c) {
+                Some(age) => ((t - birth) / age).clamp(0., 1.),
+                None => 1.
+            })
+            .collect();
+
+        self.current_state.value = modules.iter().map(| (c, _) | *c).collect();
+        self.growth = Some(growth);
+
+        self
+    }
+
+    /// Set the age at which `src` is replaced by its rule while
+    /// growing, its actions lengths growing until then
+    pub fn set_terminal_age(&mut self, src: char, age: f64) -> &mut Self {
+        if age <= 0. {
+            panic!("The terminal age of {} must be positive", src)
         }
 
+        self.terminal_ages.insert(src, age);
+
         self
     }
 
+    /// Terminal age of `src`
+    pub fn get_terminal_age(&self, src: char) -> Option<f64> {
+        self.terminal_ages.get(&src).copied()
+    }
+
+    /// Turtle state at each symbol of the state matching `symbols`
+    fn locate(&self, symbols: &[char]) -> Vec<Query> {
+        let mut queries = Vec::new();
+
+        if !self.current_state.value.chars().any(| c | symbols.contains(&c)) {
+            return queries;
+        }
+
+        let mut recorder = self.recorder();
+        let mut expanding = Vec::new();
+
+        for (i, c) in self.current_state.value.chars().enumerate() {
+            if symbols.contains(&c) {
+                queries.push(Query {
+                    symbol: c,
+                    index: i,
+                    position: recorder.position(),
+                    heading: recorder.heading(),
+                    depth: recorder.depth()
+                });
+            }
+
+            let scale = self.growth
+                .as_ref()
+                .and_then(| growth | growth.get(i))
+                .copied()
+                .unwrap_or(1.);
+
+            Self::interpret_symbol(
+                c,
+                scale,
+                &self.actions,
+                &self.interpretations,
+                &mut recorder,
+                &mut expanding
+            );
+        }
+
+        queries
+    }
+
+    /// Turtle state at each query symbol of the current state, in order
+    ///
+    /// Unlike the ABOP query modules `?P(x, y)`, the values are not
+    /// written into the symbols since they have no parameters, the
+    /// rules cannot read them, an `Environment` answering the
+    /// communication symbols can
+    pub fn queries(&self) -> Vec<Query> {
+        self.locate(&self.query_symbols)
+    }
+
+    /// Replace the communication symbols by the environment answers
+    fn communicate(&mut self) {
+        if self.environment.is_none() {
+            return;
+        }
+
+        let queries = self.locate(&self.communication_symbols);
+
+        if queries.is_empty() {
+            return;
+        }
+
+        let answers = match &mut self.environment {
+            Some(environment) => environment.respond(&queries),
+            None => return
+        };
+
+        let mut answers = queries
+            .iter()
+            .map(| query | query.index)
+            .zip(answers)
+            .peekable();
+        let mut next_value = String::with_capacity(self.current_state.value.len());
+
+        for (i, c) in self.current_state.value.chars().enumerate() {
+            match answers.next_if(| (index, _) | *index == i) {
+                Some((_, Some(answer))) => next_value.push_str(&answer),
+                _ => next_value.push(c)
+            }
+        }
+
+        self.current_state.value = prune(&next_value);
+    }
+
     fn step(&mut self) {
         // Overwriting
         self.overwrite_state_value();
+        // Splitting the new modules within the same generation
+        self.decompose();
+        // Reacting to the environment
+        self.communicate();
     }
 
     /// Executes `n` step(s)
@@ -187,10 +535,166 @@ impl Lindenmayer {
         self
     }
 
+    /// Record the current state, then the state after each of `n` step(s)
+    pub fn record_steps(&mut self, n: usize) -> Vec<Drawing> {
+        let mut drawings = vec![self.record()];
+
+        for _ in 0..n {
+            self.step();
+            drawings.push(self.record());
+        }
+
+        drawings
+    }
+
+    /// Executes `n` step(s) and write every generation on its own page
+    pub fn export_steps<E: ExportPages>(
+        &mut self,
+        exporter: &E,
+        n: usize,
+        filename: &str
+    ) -> io::Result<()> {
+        let drawings = self.record_steps(n);
+
+        exporter.save_pages(&drawings, filename)
+    }
+
     /// Set the renderer
     pub fn set_render(&mut self, cursor: Renderer) -> &mut Self {
         self.cursor = cursor.get_render_obj();
 
+        if let Some(seed) = self.seed {
+            self.cursor.set_seed(seed);
+        }
+
+        self.cursor.set_width_decay(self.width_decay);
+        self.cursor.set_restored_fields(self.restored_fields);
+
+        self
+    }
+
+    /// Set the rule of `src` in the rules table named `table`,
+    /// the table being created if needed
+    pub fn set_table_rule<T: Into<RulesWrap>>(
+        &mut self,
+        table: &str,
+        src: char,
+        dest: T
+    ) -> &mut Self {
+        if !self.is_var(src) {
+            self.set_vars(&src.to_string());
+        }
+
+        let rules_wrap: RulesWrap = dest.into();
+
+        self.tables
+            .entry(String::from(table))
+            .or_default()
+            .insert(src, rules_wrap.into());
+
+        self
+    }
+
+    /// Rule of `src` in the rules table named `table`
+    pub fn get_table_rule(&self, table: &str, src: char) -> Option<&Vec<Rule>> {
+        self.tables.get(table)?.get(&src)
+    }
+
+    /// Use the rules tables named in `tables` in turn, one per step
+    /// from the axiom, the sequence being repeated. The scheduled
+    /// table replaces the rules set with `set_rule`, which are named
+    /// `DEFAULT_TABLE`
+    pub fn set_schedule(&mut self, tables: &[&str]) -> &mut Self {
+        if tables.is_empty() {
+            panic!("The schedule needs at least one table")
+        }
+
+        let tables: Vec<String> = tables
+            .iter()
+            .map(| table | String::from(*table))
+            .collect();
+
+        self.set_schedule_fn(move | step | tables[step % tables.len()].clone())
+    }
+
+    /// Pick the rules table of each step with `schedule`, called
+    /// with the step number from 0 at the axiom
+    pub fn set_schedule_fn<F: Fn(usize) -> String + 'static>(
+        &mut self,
+        schedule: F
+    ) -> &mut Self {
+        self.schedule = Some(Box::new(schedule));
+
+        self
+    }
+
+    /// Go back to the rules set with `set_rule` at every step
+    pub fn clear_schedule(&mut self) -> &mut Self {
+        self.schedule = None;
+
+        self
+    }
+
+    /// Report the turtle state at `src` with `queries`, like the ABOP
+    /// query modules
+    pub fn set_query(&mut self, src: char) -> &mut Self {
+        if !self.query_symbols.contains(&src) {
+            self.query_symbols.push(src);
+        }
+
+        self
+    }
+
+    /// Send `src` with its turtle state to the environment after each
+    /// step, the answer replacing it
+    pub fn set_communication(&mut self, src: char) -> &mut Self {
+        if !self.communication_symbols.contains(&src) {
+            self.communication_symbols.push(src);
+        }
+
+        self
+    }
+
+    /// Set the environment answering the communication symbols
+    pub fn set_environment<E: Environment + 'static>(&mut self, environment: E) -> &mut Self {
+        self.environment = Some(Box::new(environment));
+
+        self
+    }
+
+    /// Set the interpretation rule of `src`, replacing it by `value`
+    /// while drawing without changing the state, e.g a leaf drawn
+    /// from a single symbol
+    pub fn set_interpretation(&mut self, src: char, value: &str) -> &mut Self {
+        self.interpretations.insert(src, String::from(value));
+
+        self
+    }
+
+    /// Interpretation rule of `src`
+    pub fn get_interpretation(&self, src: char) -> Option<&String> {
+        self.interpretations.get(&src)
+    }
+
+    /// Set the decomposition rule of `src`, replacing it by `value`
+    /// after each step until no decomposable symbol is left, so a
+    /// module can split into several ones within the same generation
+    pub fn set_decomposition(&mut self, src: char, value: &str) -> &mut Self {
+        self.decompositions.insert(src, String::from(value));
+
+        self
+    }
+
+    /// Decomposition rule of `src`
+    pub fn get_decomposition(&self, src: char) -> Option<&String> {
+        self.decompositions.get(&src)
+    }
+
+    /// Set the largest amount of decomposition passes per step,
+    /// stopping the recursive decompositions
+    pub fn set_decomposition_depth(&mut self, depth: usize) -> &mut Self {
+        self.decomposition_depth = depth;
+
         self
     }
 
@@ -201,6 +705,13 @@ impl Lindenmayer {
         self
     }
 
+    /// Display the drawing, for the renderers without window
+    pub fn show(&mut self) -> &mut Self {
+        self.cursor.show();
+
+        self
+    }
+
     /// Set the graphic figure position on the drawing
     pub fn set_figure_pos(&mut self, pos: ScreenPosition) -> &mut Self {
         self.cursor.set_figure_pos(pos);
@@ -208,9 +719,44 @@ impl Lindenmayer {
         self
     }
 
+    /// Make the stochastic rules and the random actions reproducible
+    pub fn set_seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+        self.cursor.set_seed(seed);
+
+        self
+    }
+
+    /// Set the blank space around the figure
+    pub fn set_margin(&mut self, margin: f64) -> &mut Self {
+        self.cursor.set_margin(margin);
+
+        self
+    }
+
+    /// Multiply the line size by `factor` on each saved state, so the
+    /// branches get thinner with their depth
+    pub fn set_width_decay(&mut self, factor: f64) -> &mut Self {
+        self.cursor.set_width_decay(factor);
+        self.width_decay = factor;
+
+        self
+    }
+
+    /// Choose the parts of the turtle state brought back by
+    /// `Do::Restore`, besides the position and the angle
+    pub fn set_restored_fields(&mut self, fields: StateFields) -> &mut Self {
+        self.cursor.set_restored_fields(fields);
+        self.restored_fields = fields;
+
+        self
+    }
+
     /// Set the drawing background color
     pub fn set_background(&mut self, r: f64, g: f64, b: f64) -> &mut Self {
         self.cursor.set_bg(r, g, b);
+        self.background = Some((r, g, b));
         
         self
     }
@@ -222,6 +768,14 @@ impl Lindenmayer {
         
         // Reset LState
         self.current_state = LState::default();
+        self.axiom = String::new();
+        self.growth = None;
+        self.generation = 0;
+
+        // Same random sequence as a new seeded system
+        if let Some(seed) = self.seed {
+            self.set_seed(seed);
+        }
 
         self
     }
@@ -246,25 +800,99 @@ impl Action for Lindenmayer {
     }
 
     fn call(&mut self, action: Self::Do) {
-        match action {
-            Do::Forward(length) => self.cursor.step_forward(length),
-            Do::Backward(length) => self.cursor.step_backward(length),
-            Do::Left(angle) => self.cursor.turn_left(angle),
-            Do::Right(angle) => self.cursor.turn_right(angle),
-            Do::PenUp => self.cursor.pen_up(),
-            Do::PenDown => self.cursor.pen_down(),
-            Do::TurnRandom => self.cursor.turn_random(),
-            Do::ColorRandom => self.cursor.color_random(),
-            Do::Save => self.cursor.save_state(),
-            Do::Restore => self.cursor.restore_state(),
-            Do::LineSize(size) => self.cursor.set_pen_size(size),
-            Do::SaveAndTurn(angle) => self.cursor.save_state_and_turn(angle),
-            Do::RestoreAndTurn(angle) => self.cursor.restore_state_and_turn(angle),
-            Do::PenColor(r, g, b) => self.cursor.set_pen_color(r, g, b),
+        execute(self.cursor.as_mut(), action);
+    }
+}
+
+/// Removes every cut symbol with the rest of its bracketed branch,
+/// up to the end outside of any branch
+#[derive(Default)]
+struct Pruner {
+    /// Depth of the branch being removed, relative to the cut
+    cutting: Option<usize>
+}
+
+impl Pruner {
+    /// Whether `c`, the next symbol, is kept
+    fn keep(&mut self, c: char) -> bool {
+        match (self.cutting, c) {
+            (None, CUT) => {
+                self.cutting = Some(0);
+                false
+            },
+            (None, _) => true,
+            (Some(depth), '[') => {
+                self.cutting = Some(depth + 1);
+                false
+            },
+            // End of the cut branch, kept to close it
+            (Some(0), ']') => {
+                self.cutting = None;
+                true
+            },
+            (Some(depth), ']') => {
+                self.cutting = Some(depth - 1);
+                false
+            },
+            (Some(_), _) => false
         }
     }
 }
 
+/// `value` without its cut branches
+fn prune(value: &str) -> String {
+    if !value.contains(CUT) {
+        return String::from(value);
+    }
+
+    let mut pruner = Pruner::default();
+
+    value.chars().filter(| c | pruner.keep(*c)).collect()
+}
+
+/// Weighted choice between the stochastic alternatives
+fn choose<'a>(rules: &'a [Rule], rng: &mut StdRng) -> &'a Rule {
+    let weights = rules
+        .iter()
+        .map(| rule | rule.weight());
+
+    let index = match WeightedIndex::new(weights) {
+        Ok(table) => table.sample(rng),
+        Err(_) => 0
+    };
+
+    &rules[index]
+}
+
+/// Forward `action` to `cursor`
+fn execute(cursor: &mut dyn Render, action: Do) {
+    match action {
+        Do::Forward(length) => cursor.step_forward(length),
+        Do::Backward(length) => cursor.step_backward(length),
+        Do::Move(length) => cursor.step_move(length),
+        Do::Left(angle) => cursor.turn_left(angle),
+        Do::Right(angle) => cursor.turn_right(angle),
+        Do::PenUp => cursor.pen_up(),
+        Do::PenDown => cursor.pen_down(),
+        Do::TurnRandom => cursor.turn_random(),
+        Do::ColorRandom => cursor.color_random(),
+        Do::Save => cursor.save_state(),
+        Do::Restore => cursor.restore_state(),
+        Do::LineSize(size) => cursor.set_pen_size(size),
+        Do::SaveAndTurn(angle) => cursor.save_state_and_turn(angle),
+        Do::RestoreAndTurn(angle) => cursor.restore_state_and_turn(angle),
+        Do::PenColor(r, g, b) => cursor.set_pen_color(r, g, b),
+        Do::Tropism(x, y, e) => cursor.set_tropism(Tropism::new(x, y, e)),
+        Do::Susceptibility(e) => cursor.set_susceptibility(e),
+        Do::ScaleWidth(factor) => cursor.scale_width(factor),
+        Do::ScaleLength(factor) => cursor.scale_length(factor),
+        Do::SwapTurns => cursor.swap_turns(),
+        Do::BeginPolygon => cursor.begin_polygon(),
+        Do::Vertex => cursor.add_vertex(),
+        Do::EndPolygon => cursor.end_polygon(),
+    }
+}
+
 impl Rules for Lindenmayer {
     type Source = char;
     type Destination = Rule;
@@ -275,7 +903,7 @@ impl Rules for Lindenmayer {
         src: Self::Source,
         dest: T
     ) -> &mut Self {
-        if self.is_var(src) == false {
+        if !self.is_var(src) {
             self.set_vars(&src.to_string());
         }
 
@@ -297,3 +925,41 @@ impl Rules for Lindenmayer {
         self.rules.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cut_top_level() {
+        assert_eq!(prune("F+F%F[+F]F"), "F+F");
+        assert_eq!(prune("%F"), "");
+    }
+
+    #[test]
+    fn cut_nested() {
+        assert_eq!(prune("F[+F%F]F"), "F[+F]F");
+        assert_eq!(prune("F[+F[-F%[+F]F]F]F"), "F[+F[-F]F]F");
+        assert_eq!(prune("F[+%F[-F]F[+F]]F"), "F[+]F");
+    }
+
+    #[test]
+    fn cut_end() {
+        assert_eq!(prune("F[+F]F%"), "F[+F]F");
+        assert_eq!(prune("F[+F%]"), "F[+F]");
+        assert_eq!(prune("FF"), "FF");
+    }
+
+    #[test]
+    fn cut_branches() {
+        let mut system = Lindenmayer::new(Renderer::Recorder(SizeType::Auto));
+
+        system
+            .set_axiom("A")
+            .set_rule('A', "F[+A]B[-A]")
+            .set_rule('B', "%")
+            .iterate(2);
+
+        assert_eq!(system.state().value, "F[+F[+A]B[-A]]");
+    }
+}
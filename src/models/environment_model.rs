@@ -0,0 +1,26 @@
+use crate::state::Pos;
+
+/// Turtle state at a query or communication symbol of the system
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub symbol: char,
+    /// Index of the symbol in the state value
+    pub index: usize,
+    pub position: Pos,
+    /// Heading in degrees, 0 pointing right
+    pub heading: f64,
+    /// Amount of saved states, the branch depth
+    pub depth: usize
+}
+
+/// World around an open L-system, e.g light or obstacles
+///
+/// The symbols have no parameters, so the turtle state is handed to
+/// the environment instead of being written into the query and
+/// communication modules like in ABOP
+pub trait Environment {
+    /// Called after each step with the communication symbols in
+    /// order, returns the symbols replacing each of them, `None`
+    /// keeping it
+    fn respond(&mut self, queries: &[Query]) -> Vec<Option<String>>;
+}
@@ -0,0 +1,22 @@
+use std::{fs, io};
+
+use crate::drawing::Drawing;
+
+pub trait Export {
+    /// Serialize `drawing` in the output format
+    fn export(&self, drawing: &Drawing) -> String;
+    /// Write the serialized `drawing` into a file at `filename`
+    fn save(&self, drawing: &Drawing, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.export(drawing))
+    }
+}
+
+/// Formats able to hold one drawing per page
+pub trait ExportPages: Export {
+    /// Serialize `drawings` in a single document, one per page
+    fn export_pages(&self, drawings: &[Drawing]) -> String;
+    /// Write the serialized `drawings` into a file at `filename`
+    fn save_pages(&self, drawings: &[Drawing], filename: &str) -> io::Result<()> {
+        fs::write(filename, self.export_pages(drawings))
+    }
+}
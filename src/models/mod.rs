@@ -2,3 +2,5 @@ pub mod rules_model;
 pub mod trace_model;
 pub mod action_model;
 pub mod render_model;
+pub mod export_model;
+pub mod environment_model;
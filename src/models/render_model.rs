@@ -1,11 +1,15 @@
 use crate::state::{
     ScreenPosition,
-    Angle
+    Angle,
+    Tropism,
+    StateFields
 };
 
 pub trait Render {
     fn step_forward(&mut self, distance: f64);
     fn step_backward(&mut self, distance: f64);
+    /// Forward without drawing, keeping the pen state
+    fn step_move(&mut self, distance: f64);
     fn turn_left(&mut self, angle: f64);
     fn turn_right(&mut self, angle: f64);
     fn turn_random(&mut self);
@@ -19,7 +23,31 @@ pub trait Render {
     fn set_pen_size(&mut self, size: f64);
     fn save_svg(&mut self, filename: &str);
     fn set_figure_pos(&mut self, pos: ScreenPosition);
+    fn set_margin(&mut self, margin: f64);
+    /// Make the random actions reproducible
+    fn set_seed(&mut self, seed: u64);
     fn set_pen_color(&mut self, r: f64, g: f64, b: f64);
+    /// Bend the heading after each forward step, saved with the state
+    fn set_tropism(&mut self, tropism: Tropism);
+    /// Change the tropism susceptibility only
+    fn set_susceptibility(&mut self, susceptibility: f64);
+    /// Multiply the pen size, saved with the state
+    fn scale_width(&mut self, factor: f64);
+    /// Multiply the forward and backward distances, saved with the state
+    fn scale_length(&mut self, factor: f64);
+    /// Swap the left and right turns, saved with the state
+    fn swap_turns(&mut self);
+    /// Multiply the pen size on each saved state, tapering the branches
+    fn set_width_decay(&mut self, factor: f64);
+    /// Choose the parts of the state brought back by `restore_state`
+    fn set_restored_fields(&mut self, fields: StateFields);
+    /// Start a polygon, filled with the pen color once ended
+    fn begin_polygon(&mut self);
+    /// Add the position to the current polygon, draw a dot without one
+    fn add_vertex(&mut self);
+    fn end_polygon(&mut self);
     fn set_bg(&mut self, r: f64, g: f64, b: f64);
     fn reset(&mut self);
+    /// Display the figure, only needed by the renderers without window
+    fn show(&mut self) {}
 }
@@ -12,12 +12,7 @@ pub trait Rules {
     ) -> &mut Self;
     fn get_rule(&self, src: Self::Source) -> Option<&Vec<Self::Destination>>;
     fn exists(&self, src: Self::Source) -> bool {
-        let dest = self.get_rule(src);
-        
-        match dest {
-            Some(_) => true,
-            None => false
-        }
+        self.get_rule(src).is_some()
     }
     fn get_rules(&mut self) -> Self::Table;
 }
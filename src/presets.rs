@@ -0,0 +1,286 @@
+use crate::{
+    lindenmayer::Lindenmayer,
+    action::Do,
+    grammar::Grammar,
+    renders::renderer::Renderer,
+    rule::Rule
+};
+
+/// Classic L-systems, mostly from "The Algorithmic Beauty of Plants"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preset {
+    KochSnowflake,
+    SierpinskiTriangle,
+    SierpinskiArrowhead,
+    Hilbert,
+    Peano,
+    Gosper,
+    LevyC,
+    Dragon,
+    BarnsleyFern,
+    /// ABOP figure 1.24 a to f
+    Plant124a,
+    Plant124b,
+    Plant124c,
+    Plant124d,
+    Plant124e,
+    Plant124f
+}
+
+/// Every preset, in declaration order
+const PRESETS: [Preset; 15] = [
+    Preset::KochSnowflake,
+    Preset::SierpinskiTriangle,
+    Preset::SierpinskiArrowhead,
+    Preset::Hilbert,
+    Preset::Peano,
+    Preset::Gosper,
+    Preset::LevyC,
+    Preset::Dragon,
+    Preset::BarnsleyFern,
+    Preset::Plant124a,
+    Preset::Plant124b,
+    Preset::Plant124c,
+    Preset::Plant124d,
+    Preset::Plant124e,
+    Preset::Plant124f
+];
+
+/// Segment length used by every preset
+const STEP: f64 = 10.;
+
+impl Preset {
+    /// Every available preset
+    pub fn all() -> &'static [Preset] {
+        &PRESETS
+    }
+
+    /// Preset name, in kebab case
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::KochSnowflake => "koch-snowflake",
+            Preset::SierpinskiTriangle => "sierpinski-triangle",
+            Preset::SierpinskiArrowhead => "sierpinski-arrowhead",
+            Preset::Hilbert => "hilbert",
+            Preset::Peano => "peano",
+            Preset::Gosper => "gosper",
+            Preset::LevyC => "levy-c",
+            Preset::Dragon => "dragon",
+            Preset::BarnsleyFern => "barnsley-fern",
+            Preset::Plant124a => "plant-1.24a",
+            Preset::Plant124b => "plant-1.24b",
+            Preset::Plant124c => "plant-1.24c",
+            Preset::Plant124d => "plant-1.24d",
+            Preset::Plant124e => "plant-1.24e",
+            Preset::Plant124f => "plant-1.24f"
+        }
+    }
+
+    /// Find a preset by its name
+    pub fn from_name(name: &str) -> Option<Preset> {
+        PRESETS
+            .iter()
+            .find(| preset | preset.name() == name)
+            .copied()
+    }
+
+    /// Sensible amount of steps
+    pub fn iterations(&self) -> usize {
+        match self {
+            Preset::KochSnowflake => 4,
+            Preset::SierpinskiTriangle => 6,
+            Preset::SierpinskiArrowhead => 7,
+            Preset::Hilbert => 6,
+            Preset::Peano => 3,
+            Preset::Gosper => 4,
+            Preset::LevyC => 12,
+            Preset::Dragon => 12,
+            Preset::BarnsleyFern => 6,
+            Preset::Plant124a => 5,
+            Preset::Plant124b => 5,
+            Preset::Plant124c => 4,
+            Preset::Plant124d => 7,
+            Preset::Plant124e => 7,
+            Preset::Plant124f => 5
+        }
+    }
+
+    /// Turn angle in degrees
+    pub fn angle(&self) -> f64 {
+        match self {
+            Preset::KochSnowflake => 60.,
+            Preset::SierpinskiTriangle => 120.,
+            Preset::SierpinskiArrowhead => 60.,
+            Preset::Hilbert => 90.,
+            Preset::Peano => 90.,
+            Preset::Gosper => 60.,
+            Preset::LevyC => 45.,
+            Preset::Dragon => 90.,
+            Preset::BarnsleyFern => 25.,
+            Preset::Plant124a => 25.7,
+            Preset::Plant124b => 20.,
+            Preset::Plant124c => 22.5,
+            Preset::Plant124d => 20.,
+            Preset::Plant124e => 25.7,
+            Preset::Plant124f => 22.5
+        }
+    }
+
+    /// Axiom and productions
+    fn productions(&self) -> (&'static str, Vec<(char, &'static str)>) {
+        match self {
+            Preset::KochSnowflake => ("F--F--F", vec![
+                ('F', "F+F--F+F")
+            ]),
+            Preset::SierpinskiTriangle => ("F-G-G", vec![
+                ('F', "F-G+F+G-F"),
+                ('G', "GG")
+            ]),
+            Preset::SierpinskiArrowhead => ("A", vec![
+                ('A', "B-A-B"),
+                ('B', "A+B+A")
+            ]),
+            Preset::Hilbert => ("A", vec![
+                ('A', "+BF-AFA-FB+"),
+                ('B', "-AF+BFB+FA-")
+            ]),
+            Preset::Peano => ("X", vec![
+                ('X', "XFYFX+F+YFXFY-F-XFYFX"),
+                ('Y', "YFXFY-F-XFYFX+F+YFXFY")
+            ]),
+            Preset::Gosper => ("A", vec![
+                ('A', "A-B--B+A++AA+B-"),
+                ('B', "+A-BB--B-A++A+B")
+            ]),
+            Preset::LevyC => ("F", vec![
+                ('F', "+F--F+")
+            ]),
+            Preset::Dragon => ("F", vec![
+                ('F', "F+G"),
+                ('G', "F-G")
+            ]),
+            Preset::BarnsleyFern => ("^X", vec![
+                ('X', "F+[[X]-X]-F[-FX]+X"),
+                ('F', "FF")
+            ]),
+            Preset::Plant124a => ("^F", vec![
+                ('F', "F[+F]F[-F]F")
+            ]),
+            Preset::Plant124b => ("^F", vec![
+                ('F', "F[+F]F[-F][F]")
+            ]),
+            Preset::Plant124c => ("^F", vec![
+                ('F', "FF-[-F+F+F]+[+F-F-F]")
+            ]),
+            Preset::Plant124d => ("^X", vec![
+                ('X', "F[+X]F[-X]+X"),
+                ('F', "FF")
+            ]),
+            Preset::Plant124e => ("^X", vec![
+                ('X', "F[+X][-X]FX"),
+                ('F', "FF")
+            ]),
+            Preset::Plant124f => ("^X", vec![
+                ('X', "F-[[X]+X]+F[+FX]-X"),
+                ('F', "FF")
+            ])
+        }
+    }
+
+    /// Textual description of the preset
+    ///
+    /// `F`, `G`, `A` and `B` draw forward, `+` and `-` turn left
+    /// and right, `[` and `]` save and restore, `^` faces up
+    pub fn grammar(&self) -> Grammar {
+        let (axiom, rules) = self.productions();
+        let angle = self.angle();
+
+        let mut actions = vec![
+            ('F', Do::Forward(STEP)),
+            ('G', Do::Forward(STEP)),
+            ('+', Do::Left(angle)),
+            ('-', Do::Right(angle)),
+            ('[', Do::Save),
+            (']', Do::Restore),
+            ('^', Do::Left(90.))
+        ];
+
+        // Only the curves drawing with their variables
+        if let Preset::SierpinskiArrowhead | Preset::Gosper = self {
+            actions.push(('A', Do::Forward(STEP)));
+            actions.push(('B', Do::Forward(STEP)));
+        }
+
+        Grammar {
+            axiom: String::from(axiom),
+            consts: String::from("+-[]^"),
+            rules: rules
+                .into_iter()
+                .map(| (src, dest) | (src, vec![Rule::new(dest, 1)]))
+                .collect(),
+            actions,
+            iterations: self.iterations(),
+            ..Grammar::default()
+        }
+    }
+
+    /// Configured system, ready to be iterated
+    pub fn build(&self, renderer: Renderer) -> Lindenmayer {
+        self.grammar().build(renderer)
+    }
+
+    /// Configured system, iterated `self.iterations()` times
+    pub fn generate(&self, renderer: Renderer) -> Lindenmayer {
+        self.grammar().generate(renderer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SizeType;
+
+    /// Symbols and drawn segments after 1, 2 and 3 steps
+    const COUNTS: [(Preset, [(usize, usize); 3]); 15] = [
+        (Preset::KochSnowflake, [(28, 12), (112, 48), (448, 192)]),
+        (Preset::SierpinskiTriangle, [(15, 9), (45, 27), (135, 81)]),
+        (Preset::SierpinskiArrowhead, [(5, 3), (17, 9), (53, 27)]),
+        (Preset::Hilbert, [(11, 3), (51, 15), (211, 63)]),
+        (Preset::Peano, [(21, 8), (201, 80), (1821, 728)]),
+        (Preset::Gosper, [(15, 7), (113, 49), (799, 343)]),
+        (Preset::LevyC, [(6, 2), (16, 4), (36, 8)]),
+        (Preset::Dragon, [(3, 2), (7, 4), (15, 8)]),
+        (Preset::BarnsleyFern, [(19, 3), (90, 18), (380, 84)]),
+        (Preset::Plant124a, [(12, 5), (62, 25), (312, 125)]),
+        (Preset::Plant124b, [(14, 5), (74, 25), (374, 125)]),
+        (Preset::Plant124c, [(21, 8), (173, 64), (1389, 512)]),
+        (Preset::Plant124d, [(13, 2), (48, 10), (157, 38)]),
+        (Preset::Plant124e, [(12, 2), (44, 10), (144, 38)]),
+        (Preset::Plant124f, [(19, 3), (90, 18), (380, 84)])
+    ];
+
+    #[test]
+    fn counts() {
+        for (preset, counts) in COUNTS {
+            for (i, (symbols, segments)) in counts.iter().enumerate() {
+                let mut grammar = preset.grammar();
+
+                grammar.iterations = i + 1;
+
+                let system = grammar.generate(Renderer::Recorder(SizeType::Auto));
+
+                assert_eq!(system.state().value.chars().count(), *symbols, "{} symbols", preset.name());
+                assert_eq!(system.record().segments(), *segments, "{} segments", preset.name());
+            }
+        }
+    }
+
+    #[test]
+    fn names() {
+        for preset in Preset::all() {
+            assert_eq!(Preset::from_name(preset.name()), Some(*preset));
+        }
+
+        assert_eq!(Preset::from_name("unknown"), None);
+    }
+}
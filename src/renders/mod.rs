@@ -4,3 +4,7 @@ pub mod turtle_headless;
 pub mod turtle;
 /// Types of renderers
 pub mod renderer;
+/// Backend-neutral drawing recorder
+pub mod recorder;
+/// Unicode preview in the terminal
+pub mod terminal;
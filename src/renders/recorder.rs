@@ -0,0 +1,373 @@
+use std::collections::LinkedList;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng
+};
+
+use crate::{
+    models::{
+        render_model::Render,
+        export_model::Export
+    },
+    drawing::{
+        Drawing,
+        Element,
+        Shape,
+        Style
+    },
+    exports::svg::Svg,
+    state::{
+        Angle,
+        State,
+        Side,
+        Pos,
+        ScreenPosition,
+        SizeType,
+        Tropism,
+        StateFields
+    }
+};
+
+/// Turtle interpreter recording the figure as a `Drawing`
+///
+/// The y axis points up and a heading of 0 degree points right
+pub struct Recorder {
+    /// Turtle state, saved on the stack
+    state: State,
+    width_decay: f64,
+    /// Parts of the state restored
+    fields: StateFields,
+    /// Polyline being drawn
+    path: Vec<Pos>,
+    /// Polygons being drawn, the last one getting the vertices
+    polygons: Vec<Vec<Pos>>,
+    drawing: Drawing,
+    stack: LinkedList<State>,
+    rng: StdRng,
+    /// SVG size type
+    pub size: SizeType,
+    figure_pos: ScreenPosition,
+    margin: f64
+}
+
+impl Recorder {
+    pub fn new(size_type: SizeType) -> Self {
+        Self {
+            state: State::default(),
+            width_decay: 1.,
+            fields: StateFields::default(),
+            path: Vec::new(),
+            polygons: Vec::new(),
+            drawing: Drawing::new(),
+            stack: LinkedList::new(),
+            rng: StdRng::from_entropy(),
+            size: size_type,
+            figure_pos: ScreenPosition::default(),
+            margin: 0.
+        }
+    }
+
+    /// Current turtle location
+    pub fn position(&self) -> Pos {
+        self.state.position
+    }
+
+    /// Current turtle heading in degrees
+    pub fn heading(&self) -> f64 {
+        self.state.angle
+    }
+
+    /// Whether the moves are drawn
+    pub fn is_down(&self) -> bool {
+        self.state.is_down
+    }
+
+    /// Current pen color and size
+    pub fn style(&self) -> Style {
+        self.state.style()
+    }
+
+    /// Amount of saved states
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Elements recorded so far, without the polyline being drawn
+    pub fn elements(&self) -> &[Element] {
+        &self.drawing.elements
+    }
+
+    /// Return the recorded figure
+    pub fn drawing(&mut self) -> Drawing {
+        self.flush();
+
+        self.drawing.clone()
+    }
+
+    /// Close the current polyline
+    fn flush(&mut self) {
+        if self.path.len() > 1 {
+            let points = std::mem::take(&mut self.path);
+
+            self.drawing.push(Element {
+                shape: Shape::Polyline(points),
+                style: self.state.style(),
+                depth: self.stack.len()
+            });
+        }
+
+        self.path.clear();
+    }
+
+    /// Close the current polyline then record `shape`
+    fn push(&mut self, shape: Shape) {
+        self.flush();
+
+        self.drawing.push(Element {
+            shape,
+            style: self.state.style(),
+            depth: self.stack.len()
+        });
+    }
+
+    /// Move along the heading, drawing if `draw` and the pen is down
+    fn go(&mut self, distance: f64, draw: bool) {
+        let distance = distance * self.state.length_scale;
+        let position = self.state.position;
+        let rad = self.state.angle.to_radians();
+
+        let next = Pos {
+            x: position.x + distance * rad.cos(),
+            y: position.y + distance * rad.sin()
+        };
+
+        if draw && self.state.is_down {
+            if self.path.is_empty() {
+                self.path.push(position);
+            }
+
+            self.path.push(next);
+        }
+
+        self.state.position = next;
+    }
+
+    /// Turn counterclockwise, negative angles turning clockwise
+    fn rotate(&mut self, angle: f64) {
+        self.state.angle = (self.state.angle + angle) % 360.;
+    }
+
+    fn turn(&mut self, angle: Angle) {
+        match angle.side {
+            Side::Left => self.turn_left(angle.value),
+            Side::Right => self.turn_right(angle.value),
+        }
+    }
+
+    /// Change the state, a new style starts a new polyline
+    fn update<F: FnOnce(&mut State)>(&mut self, change: F) {
+        let mut state = self.state;
+
+        change(&mut state);
+
+        if state.style() != self.state.style() {
+            self.flush();
+        }
+
+        self.state = state;
+    }
+}
+
+impl Render for Recorder {
+    fn step_forward(&mut self, distance: f64) {
+        self.go(distance, true);
+        self.state.bend();
+    }
+
+    fn step_backward(&mut self, distance: f64) {
+        self.go(-distance, true);
+    }
+
+    fn step_move(&mut self, distance: f64) {
+        // The polyline stops here
+        self.flush();
+
+        self.go(distance, false);
+        self.state.bend();
+    }
+
+    fn turn_left(&mut self, angle: f64) {
+        if self.state.swapped {
+            self.rotate(-angle);
+        } else {
+            self.rotate(angle);
+        }
+    }
+
+    fn turn_right(&mut self, angle: f64) {
+        if self.state.swapped {
+            self.rotate(angle);
+        } else {
+            self.rotate(-angle);
+        }
+    }
+
+    fn turn_random(&mut self) {
+        // Random angle
+        let angle = self.rng.gen::<f64>() * 360.;
+
+        // Random side value
+        let side = self.rng.gen::<u8>() % 2;
+
+        match side {
+            0 => self.turn_left(angle),
+            _ => self.turn_right(angle)
+        };
+    }
+
+    fn pen_up(&mut self) {
+        self.flush();
+
+        self.state.is_down = false;
+    }
+
+    fn pen_down(&mut self) {
+        self.state.is_down = true;
+    }
+
+    fn color_random(&mut self) {
+        let color = (
+            self.rng.gen::<u8>() as f64,
+            self.rng.gen::<u8>() as f64,
+            self.rng.gen::<u8>() as f64
+        ).into();
+
+        self.update(| state | state.color = color);
+    }
+
+    fn save_state(&mut self) {
+        // The branch depth changes
+        self.flush();
+
+        let saved = self.state.save(self.width_decay);
+
+        self.stack.push_back(saved);
+    }
+
+    fn restore_state(&mut self) {
+        self.flush();
+
+        if let Some(saved) = self.stack.pop_back() {
+            self.state.restore(saved, self.fields);
+        }
+    }
+
+    fn save_state_and_turn(&mut self, angle: Angle) {
+        // Save
+        self.save_state();
+
+        // Turn
+        self.turn(angle);
+    }
+
+    fn restore_state_and_turn(&mut self, angle: Angle) {
+        // Restore
+        self.restore_state();
+
+        // Turn
+        self.turn(angle);
+    }
+
+    fn set_pen_size(&mut self, size: f64) {
+        self.update(| state | state.width = size);
+    }
+
+    fn save_svg(&mut self, filename: &str) {
+        let mut svg = Svg::new(self.size);
+
+        svg.set_figure_pos(self.figure_pos)
+            .set_margin(self.margin)
+            .save(&self.drawing(), filename)
+            .expect("Unable to write the SVG file");
+    }
+
+    fn set_figure_pos(&mut self, pos: ScreenPosition) {
+        self.figure_pos = pos;
+    }
+
+    fn set_margin(&mut self, margin: f64) {
+        self.margin = margin;
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn set_pen_color(&mut self, r: f64, g: f64, b: f64) {
+        self.update(| state | state.color = (r, g, b).into());
+    }
+
+    fn set_tropism(&mut self, tropism: Tropism) {
+        self.state.tropism = tropism;
+    }
+
+    fn set_susceptibility(&mut self, susceptibility: f64) {
+        self.state.tropism.susceptibility = susceptibility;
+    }
+
+    fn scale_width(&mut self, factor: f64) {
+        self.update(| state | state.scale_width(factor));
+    }
+
+    fn scale_length(&mut self, factor: f64) {
+        self.state.scale_length(factor);
+    }
+
+    fn swap_turns(&mut self) {
+        self.state.swapped = !self.state.swapped;
+    }
+
+    fn set_width_decay(&mut self, factor: f64) {
+        self.width_decay = factor;
+    }
+
+    fn set_restored_fields(&mut self, fields: StateFields) {
+        self.fields = fields;
+    }
+
+    fn begin_polygon(&mut self) {
+        self.polygons.push(Vec::new());
+    }
+
+    fn add_vertex(&mut self) {
+        let position = self.state.position;
+
+        match self.polygons.last_mut() {
+            Some(polygon) => polygon.push(position),
+            None => if self.state.is_down {
+                self.push(Shape::Point(position));
+            }
+        }
+    }
+
+    fn end_polygon(&mut self) {
+        if let Some(polygon) = self.polygons.pop() {
+            if polygon.len() > 2 {
+                self.push(Shape::Polygon(polygon));
+            }
+        }
+    }
+
+    fn set_bg(&mut self, r: f64, g: f64, b: f64) {
+        self.drawing.background = Some((r, g, b).into());
+    }
+
+    fn reset(&mut self) {
+        self.state = State::default();
+        self.path.clear();
+        self.polygons.clear();
+        self.drawing = Drawing::new();
+        self.stack.clear();
+    }
+}
@@ -1,18 +1,28 @@
-use crate::{models::render_model::Render, state::SizeType};
+use crate::{
+    models::render_model::Render,
+    state::SizeType,
+    exports::terminal::Terminal
+};
 
 use super::turtle::TurtleRender;
 use super::turtle_headless::TurtleHeadless;
+use super::recorder::Recorder;
+use super::terminal::TerminalRender;
 
 pub enum Renderer {
     TurtleNormal(SizeType),
     TurtleHeadless(SizeType),
+    Recorder(SizeType),
+    Terminal(Terminal),
 }
 
 impl Renderer {
     pub fn get_render_obj(self) -> Box<dyn Render> {
         match self {
             Renderer::TurtleNormal(s) => Box::new(TurtleRender::new(s)),
-            Renderer::TurtleHeadless(s) => Box::new(TurtleHeadless::new(s))
+            Renderer::TurtleHeadless(s) => Box::new(TurtleHeadless::new(s)),
+            Renderer::Recorder(s) => Box::new(Recorder::new(s)),
+            Renderer::Terminal(t) => Box::new(TerminalRender::new(t))
         }
     }
 }
@@ -0,0 +1,167 @@
+use crate::{
+    models::{
+        render_model::Render,
+        export_model::Export
+    },
+    exports::terminal::Terminal,
+    renders::recorder::Recorder,
+    state::{
+        Angle,
+        ScreenPosition,
+        SizeType,
+        Tropism,
+        StateFields
+    }
+};
+
+/// Preview the figure with Unicode characters in the terminal,
+/// useful over SSH when no window nor SVG viewer is available
+pub struct TerminalRender {
+    cursor: Recorder,
+    /// Text preview settings
+    pub terminal: Terminal
+}
+
+impl TerminalRender {
+    pub fn new(terminal: Terminal) -> Self {
+        Self {
+            cursor: Recorder::new(SizeType::Auto),
+            terminal
+        }
+    }
+
+    /// Return the preview as text
+    pub fn preview(&mut self) -> String {
+        self.terminal.export(&self.cursor.drawing())
+    }
+}
+
+impl Render for TerminalRender {
+    fn step_forward(&mut self, distance: f64) {
+        self.cursor.step_forward(distance);
+    }
+
+    fn step_backward(&mut self, distance: f64) {
+        self.cursor.step_backward(distance);
+    }
+
+    fn step_move(&mut self, distance: f64) {
+        self.cursor.step_move(distance);
+    }
+
+    fn turn_left(&mut self, angle: f64) {
+        self.cursor.turn_left(angle);
+    }
+
+    fn turn_right(&mut self, angle: f64) {
+        self.cursor.turn_right(angle);
+    }
+
+    fn turn_random(&mut self) {
+        self.cursor.turn_random();
+    }
+
+    fn pen_up(&mut self) {
+        self.cursor.pen_up();
+    }
+
+    fn pen_down(&mut self) {
+        self.cursor.pen_down();
+    }
+
+    fn color_random(&mut self) {
+        self.cursor.color_random();
+    }
+
+    fn save_state(&mut self) {
+        self.cursor.save_state();
+    }
+
+    fn restore_state(&mut self) {
+        self.cursor.restore_state();
+    }
+
+    fn save_state_and_turn(&mut self, angle: Angle) {
+        self.cursor.save_state_and_turn(angle);
+    }
+
+    fn restore_state_and_turn(&mut self, angle: Angle) {
+        self.cursor.restore_state_and_turn(angle);
+    }
+
+    fn set_pen_size(&mut self, size: f64) {
+        self.cursor.set_pen_size(size);
+    }
+
+    fn save_svg(&mut self, filename: &str) {
+        self.cursor.save_svg(filename);
+    }
+
+    fn set_figure_pos(&mut self, pos: ScreenPosition) {
+        self.cursor.set_figure_pos(pos);
+    }
+
+    fn set_margin(&mut self, margin: f64) {
+        self.cursor.set_margin(margin);
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.cursor.set_seed(seed);
+    }
+
+    fn set_pen_color(&mut self, r: f64, g: f64, b: f64) {
+        self.cursor.set_pen_color(r, g, b);
+    }
+
+    fn set_tropism(&mut self, tropism: Tropism) {
+        self.cursor.set_tropism(tropism);
+    }
+
+    fn set_susceptibility(&mut self, susceptibility: f64) {
+        self.cursor.set_susceptibility(susceptibility);
+    }
+
+    fn scale_width(&mut self, factor: f64) {
+        self.cursor.scale_width(factor);
+    }
+
+    fn scale_length(&mut self, factor: f64) {
+        self.cursor.scale_length(factor);
+    }
+
+    fn swap_turns(&mut self) {
+        self.cursor.swap_turns();
+    }
+
+    fn set_width_decay(&mut self, factor: f64) {
+        self.cursor.set_width_decay(factor);
+    }
+
+    fn set_restored_fields(&mut self, fields: StateFields) {
+        self.cursor.set_restored_fields(fields);
+    }
+
+    fn begin_polygon(&mut self) {
+        self.cursor.begin_polygon();
+    }
+
+    fn add_vertex(&mut self) {
+        self.cursor.add_vertex();
+    }
+
+    fn end_polygon(&mut self) {
+        self.cursor.end_polygon();
+    }
+
+    fn set_bg(&mut self, r: f64, g: f64, b: f64) {
+        self.cursor.set_bg(r, g, b);
+    }
+
+    fn reset(&mut self) {
+        self.cursor.reset();
+    }
+
+    fn show(&mut self) {
+        print!("{}", self.preview());
+    }
+}
@@ -1,44 +1,37 @@
-use std::collections::LinkedList;
-use rand::{
-    Rng,
-    rngs::ThreadRng
-};
-
-use turtle::Point;
 use turtle::{
     Turtle,
-    Color,
-    rand::random
+    Color
 };
 
 use crate::models::render_model::Render;
-use crate::square::Square;
+use crate::drawing::{Rgb, Shape, Style};
+use crate::renders::recorder::Recorder;
 use crate::state::{
     Angle,
-    State,
-    Side,
     Pos,
     ScreenPosition,
-    SizeType
+    SizeType,
+    Tropism,
+    StateFields
 };
 
-impl Into<Pos> for Point {
-    fn into(self) -> Pos {
-        Pos {
-            x: self.x,
-            y: self.y,
-        }
+impl From<Rgb> for Color {
+    fn from(color: Rgb) -> Self {
+        let (r, g, b) = color.bytes();
+
+        Color::rgb(r as f64, g as f64, b as f64)
     }
 }
 
+/// Turtle drawing in a window, following a `Recorder` which does
+/// the geometry, the figure is saved with the SVG exporter
 pub struct TurtleRender {
     cursor: Turtle,
-    stack: LinkedList<State>,
-    rng: ThreadRng,
-    /// SVG size type
-    pub size: SizeType,
-    figure: Square,
-    position: ScreenPosition
+    recorder: Recorder,
+    /// Window pen style
+    style: Style,
+    /// Window pen state
+    is_down: bool
 }
 
 impl TurtleRender {
@@ -46,214 +39,258 @@ impl TurtleRender {
         // Turtle setup
         let mut turtle = Turtle::new();
 
-        // Figure area default point
-        let point = turtle.position();
-        let pos = Pos {
-            x: point.x,
-            y: point.y
-        };
-        
         // Setup turtle graphic details
         turtle.set_speed("instant");
         turtle.set_heading(0.);
 
         Self {
             cursor: turtle,
-            stack: LinkedList::new(),
-            rng: rand::thread_rng(),
-            size: size_type,
-            figure: Square {
-                top_left: pos,
-                bottom_right: pos
-            },
-            position: ScreenPosition::default()
+            recorder: Recorder::new(size_type),
+            style: Style::default(),
+            is_down: true
         }
     }
 
-    fn turn(&mut self, angle: Angle) {
-        match angle.side {
-            Side::Left => self.turn_left(angle.value),
-            Side::Right => self.turn_right(angle.value),
+    /// Copy the recorder pen style to the window turtle
+    fn sync_style(&mut self) {
+        let style = self.recorder.style();
+
+        if style != self.style {
+            self.cursor.set_pen_color(style.color);
+            self.cursor.set_pen_size(style.width);
+            self.style = style;
         }
     }
 
-    /// Return the SVG size
-    fn get_size(&mut self) -> (f64, f64) {
-        match self.size {
-            SizeType::Custom(w, h) => (w, h),
-            
-            // Figure size
-            SizeType::Auto => {
-                let figure_size = self.figure.size();
-        
-                (figure_size.w, figure_size.h)
+    /// Turn the window turtle like the recorder
+    fn sync_heading(&mut self) {
+        self.cursor.set_heading(self.recorder.heading());
+    }
+
+    /// Move the window turtle to the recorder position, drawing
+    /// only if `draw` and the recorder pen is down
+    fn sync_position(&mut self, draw: bool) {
+        let is_down = draw && self.recorder.is_down();
+
+        if is_down != self.is_down {
+            if is_down {
+                self.cursor.pen_down();
+            } else {
+                self.cursor.pen_up();
             }
+
+            self.is_down = is_down;
         }
+
+        let pos = self.recorder.position();
+
+        self.sync_style();
+        self.cursor.go_to([pos.x, pos.y]);
+        self.sync_heading();
     }
 
-    /// Return the final figure position (top left angle)
-    fn get_position(&mut self) -> Point {
-        let svg_size = self.get_size();
-        let pen_offset = self.cursor.pen_size();
+    /// Fill the area between `points` with the pen color
+    fn fill(&mut self, points: &[Pos]) {
+        if self.is_down {
+            self.cursor.pen_up();
+            self.is_down = false;
+        }
+
+        self.sync_style();
+        self.cursor.set_fill_color(self.style.color);
+        self.cursor.go_to([points[0].x, points[0].y]);
+        self.cursor.begin_fill();
+
+        for point in points {
+            self.cursor.go_to([point.x, point.y]);
+        }
+
+        self.cursor.end_fill();
+        self.sync_position(false);
+    }
 
-        let figure_size = self.figure.size();
+    /// Draw the shape recorded after the first `count` elements, if any
+    fn draw_last(&mut self, count: usize) {
+        let elements = self.recorder.elements();
 
-        // Offset including the pen line size
-        let x_offset = (svg_size.0 - figure_size.w - pen_offset) / 2.;
-        let y_offset = (svg_size.1 - figure_size.h - pen_offset) / 2.;
+        let shape = match elements.last() {
+            Some(element) if elements.len() > count => element.shape.clone(),
+            _ => return
+        };
 
-        match self.position {
-            ScreenPosition::Coord(x, y) => (x, y),
-            ScreenPosition::Center => (-self.figure.top_left.x, -self.figure.top_left.y),
-            ScreenPosition::TopLeft =>(-self.figure.top_left.x - x_offset, -self.figure.top_left.y + y_offset),
-            ScreenPosition::TopRight => (-self.figure.top_left.x + x_offset, -self.figure.top_left.y + y_offset),
-            ScreenPosition::BottomLeft => (-self.figure.top_left.x - x_offset, -self.figure.top_left.y - y_offset),
-            ScreenPosition::BottomRight => (-self.figure.top_left.x + x_offset, -self.figure.top_left.y - y_offset),
-        }.into()
+        match shape {
+            Shape::Polygon(points) => self.fill(&points),
+            Shape::Point(point) => {
+                // Octagon as wide as the pen
+                let radius = self.recorder.style().width / 2.;
+                let points: Vec<Pos> = (0..8)
+                    .map(| i | {
+                        let angle = (i as f64 * 45.).to_radians();
+
+                        Pos {
+                            x: point.x + radius * angle.cos(),
+                            y: point.y + radius * angle.sin()
+                        }
+                    })
+                    .collect();
+
+                self.fill(&points);
+            },
+            Shape::Polyline(_) => {}
+        }
     }
 }
 
 impl Render for TurtleRender {
     fn step_forward(&mut self, distance: f64) {
-        self.cursor.forward(distance);
-
-        // Updating area max size
-        self.figure.update_max_area(self.cursor.position().into());
+        self.recorder.step_forward(distance);
+        self.sync_position(true);
     }
 
     fn step_backward(&mut self, distance: f64) {
-        self.cursor.backward(distance);
+        self.recorder.step_backward(distance);
+        self.sync_position(true);
+    }
 
-        // Updating area max size
-        self.figure.update_max_area(self.cursor.position().into());
+    fn step_move(&mut self, distance: f64) {
+        self.recorder.step_move(distance);
+        self.sync_position(false);
     }
 
     fn turn_left(&mut self, angle: f64) {
-        self.cursor.left(angle);
+        self.recorder.turn_left(angle);
+        self.sync_heading();
     }
 
     fn turn_right(&mut self, angle: f64) {
-        self.cursor.right(angle);
+        self.recorder.turn_right(angle);
+        self.sync_heading();
     }
 
     fn turn_random(&mut self) {
-        // Random angle
-        let angle = self.rng.gen::<f64>() * 360.;
-
-        // Random side value
-        let side = self.rng.gen::<u8>() % 2;
-
-        // Turn and get turn side to save the angle
-        match side {
-            0 => self.turn_left(angle),
-            _ => self.turn_right(angle)
-        };
+        self.recorder.turn_random();
+        self.sync_heading();
     }
 
     fn pen_up(&mut self) {
-        self.cursor.pen_up();
+        self.recorder.pen_up();
     }
 
     fn pen_down(&mut self) {
-        self.cursor.pen_down()
+        self.recorder.pen_down();
     }
 
     fn color_random(&mut self) {
-        let color = random::<Color>().opaque();
-    
-        self.cursor.set_pen_color(color);
+        self.recorder.color_random();
+        self.sync_style();
     }
 
     fn save_state(&mut self) {
-        // Turtle position
-        let pos = self.cursor.position();
+        self.recorder.save_state();
+        self.sync_style();
+    }
 
-        let state = State {
-            position: Pos {
-                x: pos.x,
-                y: pos.y
-            },
-            angle: self.cursor.heading()
-        };
+    fn restore_state(&mut self) {
+        self.recorder.restore_state();
+        self.sync_position(false);
+    }
 
-        self.stack.push_back(state);
+    fn save_state_and_turn(&mut self, angle: Angle) {
+        self.recorder.save_state_and_turn(angle);
+        self.sync_style();
+        self.sync_heading();
     }
 
-    fn restore_state(&mut self) {
-        self.pen_up();
+    fn restore_state_and_turn(&mut self, angle: Angle) {
+        self.recorder.restore_state_and_turn(angle);
+        self.sync_position(false);
+    }
 
-        let state = self.stack.pop_back();
+    fn set_pen_size(&mut self, size: f64) {
+        self.recorder.set_pen_size(size);
+        self.sync_style();
+    }
 
-        self.cursor.set_heading(0.);
+    fn save_svg(&mut self, filename: &str) {
+        self.recorder.save_svg(filename);
+    }
 
-        match state {
-            Some(value) => {
-                let pos = value.position;
-                let angle = value.angle;
-                
-                self.cursor.go_to([pos.x, pos.y]);
-                self.cursor.set_heading(angle);
-            },
-            None => return
-        };
+    fn set_figure_pos(&mut self, pos: ScreenPosition) {
+        self.recorder.set_figure_pos(pos);
+    }
 
-        self.pen_down();
+    fn set_margin(&mut self, margin: f64) {
+        self.recorder.set_margin(margin);
     }
 
-    fn set_pen_size(&mut self, size: f64) {
-        self.cursor.set_pen_size(size);
+    fn set_seed(&mut self, seed: u64) {
+        self.recorder.set_seed(seed);
     }
 
-    fn save_svg(&mut self, filename: &str) {
-        // SVG size
-        let (w, h) = self.get_size();
-        // Figure pos / size
-        let mut fig_pos = self.get_position();
-        let fig_size = self.figure.size();
-        
-        // Square center
-        fig_pos.x -= fig_size.w / 2.;
-        fig_pos.y += fig_size.h / 2.;
+    fn set_pen_color(&mut self, r: f64, g: f64, b: f64) {
+        self.recorder.set_pen_color(r, g, b);
+        self.sync_style();
+    }
 
-        self.cursor.drawing_mut().set_size((w as u32, h as u32));
-        self.cursor.drawing_mut().set_center(fig_pos);
-        self.cursor.drawing().save_svg(filename);
+    fn set_tropism(&mut self, tropism: Tropism) {
+        self.recorder.set_tropism(tropism);
     }
 
-    fn set_figure_pos(&mut self, pos: ScreenPosition) {
-        self.position = pos;
+    fn set_susceptibility(&mut self, susceptibility: f64) {
+        self.recorder.set_susceptibility(susceptibility);
     }
 
-    fn save_state_and_turn(&mut self, angle: Angle) {
-        // Save
-        self.save_state();
+    fn scale_width(&mut self, factor: f64) {
+        self.recorder.scale_width(factor);
+        self.sync_style();
+    }
 
-        // Turn
-        self.turn(angle);
+    fn scale_length(&mut self, factor: f64) {
+        self.recorder.scale_length(factor);
     }
 
-    fn restore_state_and_turn(&mut self, angle: Angle) {
-        // Restore
-        self.restore_state();
+    fn swap_turns(&mut self) {
+        self.recorder.swap_turns();
+    }
 
-        // Turn
-        self.turn(angle);
+    fn set_width_decay(&mut self, factor: f64) {
+        self.recorder.set_width_decay(factor);
     }
 
-    fn set_pen_color(&mut self, r: f64, g: f64, b: f64) {
-        let color = Color::rgb(r, g, b);
-        
-        self.cursor.set_pen_color(color);
+    fn set_restored_fields(&mut self, fields: StateFields) {
+        self.recorder.set_restored_fields(fields);
+    }
+
+    fn begin_polygon(&mut self) {
+        self.recorder.begin_polygon();
+    }
+
+    fn add_vertex(&mut self) {
+        let count = self.recorder.elements().len();
+
+        self.recorder.add_vertex();
+        self.draw_last(count);
+    }
+
+    fn end_polygon(&mut self) {
+        let count = self.recorder.elements().len();
+
+        self.recorder.end_polygon();
+        self.draw_last(count);
     }
 
     fn set_bg(&mut self, r: f64, g: f64, b: f64) {
-        let color = Color::rgb(r, g, b);
+        let color: Rgb = (r, g, b).into();
 
+        self.recorder.set_bg(r, g, b);
         self.cursor.drawing_mut().set_background_color(color);
     }
 
     fn reset(&mut self) {
+        self.recorder.reset();
         self.cursor.reset();
+        self.style = Style::default();
+        self.is_down = true;
 
         self.cursor.set_speed("instant");
         self.cursor.set_heading(0.);
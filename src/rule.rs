@@ -1,5 +1,5 @@
 /// Representing a lindenmayer rule
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct Rule {
     /// Rule value as str
     pub value: String,
@@ -21,29 +21,29 @@ impl Rule {
     }
 }
 
-impl Into<Rule> for &str {
-    fn into(self) -> Rule {
+impl From<&str> for Rule {
+    fn from(value: &str) -> Self {
         Rule {
-            value: String::from(self),
+            value: String::from(value),
             weight: 100
         }
     }
 }
 
-impl Into<Rule> for char {
-    fn into(self) -> Rule {
+impl From<char> for Rule {
+    fn from(value: char) -> Self {
         Rule {
-            value: String::from(self),
+            value: String::from(value),
             weight: 100
         }
     }
 }
 
-impl Into <Rule> for (&str, usize) {
-    fn into(self) -> Rule {
+impl From<(&str, usize)> for Rule {
+    fn from(value: (&str, usize)) -> Self {
         Rule {
-            value: String::from(self.0),
-            weight: self.1
+            value: String::from(value.0),
+            weight: value.1
         }
     }
 }
@@ -56,9 +56,9 @@ impl From<&str> for RulesWrap {
     }
 }
 
-impl Into<Vec<Rule>> for RulesWrap {
-    fn into(self) -> Vec<Rule> {
-        self.0
+impl From<RulesWrap> for Vec<Rule> {
+    fn from(value: RulesWrap) -> Self {
+        value.0
     }
 }
 
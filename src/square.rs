@@ -1,6 +1,6 @@
 use crate::state::{Size, Pos};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Square {
     /// Top left position
     pub top_left: Pos,
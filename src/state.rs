@@ -1,5 +1,7 @@
+use crate::drawing::{Rgb, Style};
+
 /// Location at screen
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Pos {
     pub x: f64,
     pub y: f64
@@ -14,7 +16,7 @@ impl Default for Pos {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Size {
     pub w: f64,
     pub h: f64
@@ -29,19 +31,174 @@ impl Default for Size {
     }
 }
 
+/// Bending of the heading towards a direction after each forward
+/// step, the ABOP tropism vector `T` and susceptibility `e`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tropism {
+    pub x: f64,
+    pub y: f64,
+    pub susceptibility: f64
+}
+
+impl Tropism {
+    pub fn new(x: f64, y: f64, susceptibility: f64) -> Self {
+        Self {
+            x,
+            y,
+            susceptibility
+        }
+    }
+
+    /// Left turn in degrees bending `heading` (in degrees, the y axis
+    /// pointing up) towards the vector, the larger the further
+    /// the heading is from it
+    pub fn bend(&self, heading: f64) -> f64 {
+        let rad = heading.to_radians();
+        let torque = rad.cos() * self.y - rad.sin() * self.x;
+
+        (self.susceptibility * torque).to_degrees()
+    }
+}
+
 /// State of the "turtle" (cursor) in the render
+#[derive(Debug, Clone, Copy)]
 pub struct State {
     pub position: Pos,
-    pub angle: f64
+    pub angle: f64,
+    pub tropism: Tropism,
+    /// Pen size
+    pub width: f64,
+    /// Factor of the forward and backward distances
+    pub length_scale: f64,
+    /// Pen color
+    pub color: Rgb,
+    pub is_down: bool,
+    /// Left and right turns swapped
+    pub swapped: bool
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            position: Pos::default(),
+            angle: 0.,
+            tropism: Tropism::default(),
+            width: 1.,
+            length_scale: 1.,
+            color: Rgb::default(),
+            is_down: true,
+            swapped: false
+        }
+    }
 }
 
-#[derive(Clone)]
+impl State {
+    /// Pen color and size
+    pub fn style(&self) -> Style {
+        Style {
+            color: self.color,
+            width: self.width
+        }
+    }
+
+    /// Bend the angle (in degrees, the y axis pointing up) towards
+    /// the tropism vector, after a forward step
+    pub fn bend(&mut self) {
+        self.angle = (self.angle + self.tropism.bend(self.angle)) % 360.;
+    }
+
+    /// Multiply the pen size
+    pub fn scale_width(&mut self, factor: f64) {
+        self.width *= factor;
+    }
+
+    /// Multiply the forward and backward distances
+    pub fn scale_length(&mut self, factor: f64) {
+        self.length_scale *= factor;
+    }
+
+    /// Copy of the state to push on the stack, the pen of the
+    /// new branch getting thinner by `width_decay`
+    pub fn save(&mut self, width_decay: f64) -> State {
+        let saved = *self;
+
+        self.scale_width(width_decay);
+
+        saved
+    }
+
+    /// Go back to the position and the angle of `saved`, and
+    /// to the parts of it chosen by `fields`
+    pub fn restore(&mut self, saved: State, fields: StateFields) {
+        self.position = saved.position;
+        self.angle = saved.angle;
+        self.swapped = saved.swapped;
+
+        if fields.tropism {
+            self.tropism = saved.tropism;
+        }
+
+        if fields.width {
+            self.width = saved.width;
+        }
+
+        if fields.length_scale {
+            self.length_scale = saved.length_scale;
+        }
+
+        if fields.color {
+            self.color = saved.color;
+        }
+
+        if fields.is_down {
+            self.is_down = saved.is_down;
+        }
+    }
+}
+
+/// Parts of the saved state brought back when it is restored,
+/// besides the position and the angle, all of them by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateFields {
+    pub tropism: bool,
+    pub width: bool,
+    pub length_scale: bool,
+    pub color: bool,
+    pub is_down: bool
+}
+
+impl Default for StateFields {
+    fn default() -> Self {
+        Self {
+            tropism: true,
+            width: true,
+            length_scale: true,
+            color: true,
+            is_down: true
+        }
+    }
+}
+
+impl StateFields {
+    /// Only the position and the angle
+    pub fn none() -> Self {
+        Self {
+            tropism: false,
+            width: false,
+            length_scale: false,
+            color: false,
+            is_down: false
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Side {
     Left,
     Right
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct Angle {
     pub side: Side,
     pub value: f64
@@ -65,8 +222,10 @@ impl Default for Angle {
     }
 }
 
+#[derive(Clone, Copy, Default)]
 pub enum ScreenPosition {
     Coord(f64, f64),
+    #[default]
     Center,
     TopLeft,
     TopRight,
@@ -74,13 +233,75 @@ pub enum ScreenPosition {
     BottomRight
 }
 
-impl Default for ScreenPosition {
-    fn default() -> Self {
-        ScreenPosition::Center
+impl ScreenPosition {
+    /// Top left corner of a `figure` sized area placed on a `canvas`,
+    /// for a y axis pointing down
+    pub fn origin(&self, canvas: &Size, figure: &Size) -> (f64, f64) {
+        let (dw, dh) = (canvas.w - figure.w, canvas.h - figure.h);
+
+        match self {
+            ScreenPosition::Coord(x, y) => (*x, *y),
+            ScreenPosition::Center => (dw / 2., dh / 2.),
+            ScreenPosition::TopLeft => (0., 0.),
+            ScreenPosition::TopRight => (dw, 0.),
+            ScreenPosition::BottomLeft => (0., dh),
+            ScreenPosition::BottomRight => (dw, dh),
+        }
+    }
+}
+
+/// How the figure is scaled into a fixed size canvas
+#[derive(Debug, Clone, Copy, Default)]
+pub enum FitMode {
+    /// Largest size showing the whole figure, keeping its aspect ratio
+    #[default]
+    Contain,
+    /// Smallest size covering the whole canvas, keeping its aspect ratio
+    Cover,
+    /// Fill the canvas, ignoring the aspect ratio
+    Stretch,
+    /// Keep the figure size
+    None
+}
+
+impl FitMode {
+    /// Horizontal and vertical scales of a `figure` sized area
+    /// fitting an `area` sized area
+    pub fn scale(&self, area: &Size, figure: &Size) -> (f64, f64) {
+        let sx = if figure.w > 0. { area.w / figure.w } else { f64::INFINITY };
+        let sy = if figure.h > 0. { area.h / figure.h } else { f64::INFINITY };
+
+        // A flat figure is only scaled along its length
+        let uniform = | s: f64 | if s.is_finite() { (s, s) } else { (1., 1.) };
+
+        match self {
+            FitMode::Contain => uniform(sx.min(sy)),
+            FitMode::Cover if sx.is_finite() && sy.is_finite() => uniform(sx.max(sy)),
+            FitMode::Cover => uniform(sx.min(sy)),
+            FitMode::Stretch => (
+                if sx.is_finite() { sx } else { 1. },
+                if sy.is_finite() { sy } else { 1. }
+            ),
+            FitMode::None => (1., 1.)
+        }
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum SizeType {
     Custom(f64, f64),
+    /// Fixed size canvas, the figure is scaled with `FitMode`
+    Fit(f64, f64, FitMode),
     Auto
 }
+
+impl SizeType {
+    /// Canvas size for a figure of size `figure`
+    pub fn canvas(&self, figure: &Size) -> Size {
+        match self {
+            SizeType::Custom(w, h) => Size { w: *w, h: *h },
+            SizeType::Fit(w, h, _) => Size { w: *w, h: *h },
+            SizeType::Auto => *figure
+        }
+    }
+}